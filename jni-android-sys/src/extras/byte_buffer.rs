@@ -0,0 +1,43 @@
+#![cfg(any(feature = "all", feature = "java-nio-ByteBuffer"))]
+use super::java;
+use jni_glue::*;
+
+
+
+impl java::nio::ByteBuffer {
+    /// Create a new direct `java.nio.ByteBuffer` wrapping `memory`, without copying it.  Returns `None` if the VM
+    /// doesn't support direct buffers.
+    ///
+    /// **unsafe**:  The returned [Local] can't outlive `memory`, but the Java object can - Java code may stash it away,
+    /// or you might convert it into a [Global].  Accessing the buffer after `memory` is gone is a use-after-free.
+    ///
+    /// [Local]:    ../../../jni_glue/struct.Local.html
+    /// [Global]:   ../../../jni_glue/struct.Global.html
+    pub unsafe fn new_direct<'env>(env: &'env Env, memory: &'env mut [u8]) -> Option<Local<'env, Self>> {
+        let buffer = env.new_direct_byte_buffer(memory)?;
+        Some(Local::from_env_object(env.as_jni_env() as *const _, buffer))
+    }
+
+    /// Create a new direct `java.nio.ByteBuffer` wrapping `memory`, without copying it.  Returns `None` if the VM
+    /// doesn't support direct buffers.
+    pub fn new_direct_static<'env>(env: &'env Env, memory: &'static mut [u8]) -> Option<Local<'env, Self>> {
+        let buffer = env.new_direct_byte_buffer_static(memory)?;
+        Some(unsafe { Local::from_env_object(env.as_jni_env() as *const _, buffer) })
+    }
+
+    /// The memory backing this buffer (ignoring position and limit), or `None` if this isn't a direct buffer.
+    ///
+    /// **unsafe**:  Java code (or another native thread) may write to the buffer while the slice is borrowed.
+    pub unsafe fn direct_bytes(&self) -> Option<&[u8]> {
+        Env::from_ptr(self.0.env).get_direct_buffer_address(self.0.object)
+    }
+
+    /// The memory backing this buffer (ignoring position and limit), or `None` if this isn't a direct buffer.
+    ///
+    /// **unsafe**:  Java code (or another native thread) may access the buffer while the slice is borrowed, and nothing
+    /// stops you from calling this twice to create aliasing `&mut [u8]`s.
+    #[allow(clippy::mut_from_ref)]
+    pub unsafe fn direct_bytes_mut(&self) -> Option<&mut [u8]> {
+        Env::from_ptr(self.0.env).get_direct_buffer_address_mut(self.0.object)
+    }
+}
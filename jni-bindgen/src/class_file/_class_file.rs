@@ -0,0 +1,9 @@
+//! Class file parsing jreflection doesn't (yet?) do for us:  attributes such as generic `Signature`s.
+
+#[allow(unused_imports)] use super::*;
+
+mod attributes;
+mod signature;
+
+pub use attributes::*;
+pub use signature::*;
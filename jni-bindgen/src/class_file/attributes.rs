@@ -0,0 +1,476 @@
+#[allow(unused_imports)] use super::*;
+
+use std::collections::*;
+use std::io;
+
+
+
+/// The attributes of a class file (and it's fields/methods) that jreflection skips over.
+/// 
+/// See [Java SE 8 &sect; 4.7](https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-4.html#jvms-4.7).
+#[derive(Clone, Debug, Default)]
+pub struct ClassAttributes {
+    /// The class's generic `Signature`, if any - e.g. "<T:Ljava/lang/Object;>Ljava/lang/Object;Ljava/util/List<TT;>;"
+    pub signature:  Option<String>,
+
+    /// Optional attributes that failed to parse, and were treated as absent - e.g. "method foo(I)V: unable to read
+    /// MethodParameters: Unexpected end of class file".
+    pub warnings:   Vec<String>,
+
+    fields:         HashMap<String, MemberAttributes>,
+    methods:        HashMap<String, MemberAttributes>,
+}
+
+/// The attributes of a field or method that jreflection skips over.
+#[derive(Clone, Debug, Default)]
+pub struct MemberAttributes {
+    /// The field/method's generic `Signature`, if any - e.g. "(TE;)Z" for `boolean add(E)`.
+    pub signature:  Option<String>,
+
+    /// Is the field/method (return value) annotated `@NonNull` or `@Nullable`?  See [Nullability] for which annotations
+    /// are recognized.
+    ///
+    /// [Nullability]:  enum.Nullability.html
+    pub nullability: Option<Nullability>,
+
+    /// The method's parameter names, from the `MethodParameters` attribute - or failing that, the `LocalVariableTable`
+    /// debug info.  Empty if neither was available, and `None` for any unnamed parameters.
+    pub parameter_names: Vec<Option<String>>,
+}
+
+/// Whether or not a field/method (return value) can be null, according to its annotations.
+///
+/// Recognizes the `NonNull`/`Nullable` annotations of androidx, `android.annotation`, the Android support library,
+/// JSR-305, JetBrains, the Checker Framework, and JSpecify - as either declaration or type annotations, visible at
+/// runtime or not.  If a member has both, `Nullable` wins.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Nullability {
+    NonNull,
+    Nullable,
+}
+
+impl Nullability {
+    fn from_annotation_type(descriptor: &str) -> Option<Self> {
+        match descriptor {
+            "Landroidx/annotation/NonNull;"                             => Some(Nullability::NonNull),
+            "Landroid/annotation/NonNull;"                              => Some(Nullability::NonNull),
+            "Landroid/support/annotation/NonNull;"                      => Some(Nullability::NonNull),
+            "Ljavax/annotation/Nonnull;"                                => Some(Nullability::NonNull),
+            "Lorg/jetbrains/annotations/NotNull;"                       => Some(Nullability::NonNull),
+            "Lorg/checkerframework/checker/nullness/qual/NonNull;"      => Some(Nullability::NonNull),
+            "Lorg/jspecify/annotations/NonNull;"                        => Some(Nullability::NonNull),
+
+            "Landroidx/annotation/Nullable;"                            => Some(Nullability::Nullable),
+            "Landroid/annotation/Nullable;"                             => Some(Nullability::Nullable),
+            "Landroid/support/annotation/Nullable;"                     => Some(Nullability::Nullable),
+            "Ljavax/annotation/Nullable;"                               => Some(Nullability::Nullable),
+            "Ljavax/annotation/CheckForNull;"                           => Some(Nullability::Nullable),
+            "Lorg/jetbrains/annotations/Nullable;"                      => Some(Nullability::Nullable),
+            "Lorg/checkerframework/checker/nullness/qual/Nullable;"     => Some(Nullability::Nullable),
+            "Lorg/jspecify/annotations/Nullable;"                       => Some(Nullability::Nullable),
+
+            _ => None,
+        }
+    }
+
+    fn merge(a: Option<Self>, b: Option<Self>) -> Option<Self> {
+        match (a, b) {
+            (Some(Nullability::Nullable), _) | (_, Some(Nullability::Nullable)) => Some(Nullability::Nullable),
+            (Some(Nullability::NonNull), _) | (_, Some(Nullability::NonNull))   => Some(Nullability::NonNull),
+            (None, None)                                                        => None,
+        }
+    }
+}
+
+impl ClassAttributes {
+    pub fn field(&self, name: &str, descriptor: &str) -> Option<&MemberAttributes> {
+        self.fields.get(&member_key(name, descriptor))
+    }
+
+    pub fn method(&self, name: &str, descriptor: &str) -> Option<&MemberAttributes> {
+        self.methods.get(&member_key(name, descriptor))
+    }
+
+    /// Read the attributes of an entire class file.
+    pub fn read(class_file: &[u8]) -> io::Result<Self> {
+        let mut reader = Reader(class_file);
+        if reader.u4()? != 0xCAFEBABE { return io_data_err!("Invalid header magic, not a class file"); }
+        reader.skip(4)?; // minor_version, major_version
+
+        let constants = Constants::read(&mut reader)?;
+        reader.skip(6)?; // access_flags, this_class, super_class
+        let interfaces_count = reader.u2()? as usize;
+        reader.skip(2 * interfaces_count)?;
+
+        let mut warnings = Vec::new();
+        let fields  = read_members(&mut reader, &constants, "field",  &mut warnings)?;
+        let methods = read_members(&mut reader, &constants, "method", &mut warnings)?;
+
+        let mut class = Self { warnings, fields, methods, ..Default::default() };
+        for (name, mut data) in read_attributes(&mut reader, &constants)? {
+            if name == "Signature" { class.signature = Some(constants.utf8(data.u2()?)?.to_owned()); }
+        }
+        Ok(class)
+    }
+}
+
+fn member_key(name: &str, descriptor: &str) -> String { format!("{}\x1f{}", name, descriptor) }
+
+fn read_members(reader: &mut Reader, constants: &Constants, kind: &str, warnings: &mut Vec<String>) -> io::Result<HashMap<String, MemberAttributes>> {
+    let count = reader.u2()? as usize;
+    let mut members = HashMap::with_capacity(count);
+    for _ in 0..count {
+        let access_flags = reader.u2()?;
+        let name        = constants.utf8(reader.u2()?)?;
+        let descriptor  = constants.utf8(reader.u2()?)?;
+
+        // Parameter names and nullability are nice-to-haves:  attributes we fail to parse are treated as absent, with a warning.
+        let mut optional = |attribute: &str, err: io::Error| warnings.push(format!("{} {}{}: unable to read {}: {}", kind, name, descriptor, attribute, err));
+
+        let mut member = MemberAttributes::default();
+        let mut local_variable_names = Vec::new();
+        for (attribute, mut data) in read_attributes(reader, constants)? {
+            match attribute {
+                "Signature"         => member.signature = Some(constants.utf8(data.u2()?)?.to_owned()),
+                "MethodParameters"  => match read_method_parameters(&mut data, constants) {
+                    Ok(names)   => member.parameter_names = names,
+                    Err(err)    => optional(attribute, err),
+                },
+                "RuntimeVisibleAnnotations" | "RuntimeInvisibleAnnotations" => match read_annotations_nullability(&mut data, constants) {
+                    Ok(nullability) => member.nullability = Nullability::merge(member.nullability, nullability),
+                    Err(err)        => optional(attribute, err),
+                },
+                "RuntimeVisibleTypeAnnotations" | "RuntimeInvisibleTypeAnnotations" => match read_type_annotations_nullability(&mut data, constants) {
+                    Ok(nullability) => member.nullability = Nullability::merge(member.nullability, nullability),
+                    Err(err)        => optional(attribute, err),
+                },
+                "Code"              => match read_code_parameter_names(&mut data, constants, access_flags, descriptor) {
+                    Ok(names)   => local_variable_names = names,
+                    Err(err)    => optional(attribute, err),
+                },
+                _                   => {},
+            }
+        }
+        if member.parameter_names.iter().all(|name| name.is_none()) && local_variable_names.iter().any(|name| name.is_some()) {
+            member.parameter_names = local_variable_names;
+        }
+        members.insert(member_key(name, descriptor), member);
+    }
+    Ok(members)
+}
+
+/// [Java SE 8 &sect; 4.7.16](https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-4.html#jvms-4.7.16)
+fn read_annotations_nullability(data: &mut Reader, constants: &Constants) -> io::Result<Option<Nullability>> {
+    let count = data.u2()? as usize;
+    let mut nullability = None;
+    for _ in 0..count {
+        nullability = Nullability::merge(nullability, read_annotation_nullability(data, constants)?);
+    }
+    Ok(nullability)
+}
+
+/// [Java SE 8 &sect; 4.7.20](https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-4.html#jvms-4.7.20) - only
+/// annotations on the field type or method return type itself (not e.g. array elements or type arguments) count.
+fn read_type_annotations_nullability(data: &mut Reader, constants: &Constants) -> io::Result<Option<Nullability>> {
+    let count = data.u2()? as usize;
+    let mut nullability = None;
+    for _ in 0..count {
+        let target_type = data.u1()?;
+        match target_type { // target_info
+            0x00 | 0x01 | 0x16  => data.skip(1)?, // type_parameter_target, formal_parameter_target
+            0x10 | 0x17         => data.skip(2)?, // supertype_target, throws_target
+            0x11 | 0x12         => data.skip(2)?, // type_parameter_bound_target
+            0x13..=0x15         => {}, // empty_target:  field, method return, receiver
+            unknown             => return io_data_err!("Unexpected type annotation target_type 0x{:02x} outside of Code", unknown),
+        }
+        let path_length = data.u1()? as usize;
+        data.skip(2 * path_length)?;
+        let annotation = read_annotation_nullability(data, constants)?;
+        if (target_type == 0x13 || target_type == 0x14) && path_length == 0 {
+            nullability = Nullability::merge(nullability, annotation);
+        }
+    }
+    Ok(nullability)
+}
+
+fn read_annotation_nullability(data: &mut Reader, constants: &Constants) -> io::Result<Option<Nullability>> {
+    let nullability = Nullability::from_annotation_type(constants.utf8(data.u2()?)?);
+    let pairs = data.u2()? as usize;
+    for _ in 0..pairs {
+        data.skip(2)?; // element_name_index
+        skip_element_value(data, constants)?;
+    }
+    Ok(nullability)
+}
+
+fn skip_element_value(data: &mut Reader, constants: &Constants) -> io::Result<()> {
+    match data.u1()? {
+        b'B' | b'C' | b'D' | b'F' | b'I' | b'J' | b'S' | b'Z' | b's' | b'c' => data.skip(2)?,
+        b'e' => data.skip(4)?,
+        b'@' => { read_annotation_nullability(data, constants)?; },
+        b'[' => {
+            let count = data.u2()? as usize;
+            for _ in 0..count { skip_element_value(data, constants)?; }
+        },
+        unknown => return io_data_err!("Unexpected annotation element_value tag {:?}", unknown as char),
+    }
+    Ok(())
+}
+
+/// [Java SE 8 &sect; 4.7.24](https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-4.html#jvms-4.7.24)
+fn read_method_parameters(data: &mut Reader, constants: &Constants) -> io::Result<Vec<Option<String>>> {
+    let count = data.u1()? as usize;
+    let mut names = Vec::with_capacity(count);
+    for _ in 0..count {
+        let name_index = data.u2()?;
+        data.skip(2)?; // access_flags
+        names.push(if name_index == 0 { None } else { Some(constants.utf8(name_index)?.to_owned()) });
+    }
+    Ok(names)
+}
+
+/// Parameter names from the `LocalVariableTable` nested inside a method's `Code` attribute, if it has one.
+/// See [Java SE 8 &sect; 4.7.3](https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-4.html#jvms-4.7.3) and
+/// [&sect; 4.7.13](https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-4.html#jvms-4.7.13).
+fn read_code_parameter_names(data: &mut Reader, constants: &Constants, access_flags: u16, descriptor: &str) -> io::Result<Vec<Option<String>>> {
+    const ACC_STATIC : u16 = 0x0008;
+
+    // Parameters occupy the first local variable slots - after `this`, with longs and doubles taking up two slots.
+    let mut slots = Vec::new();
+    let mut next_slot = if access_flags & ACC_STATIC != 0 { 0 } else { 1 };
+    for arg in jreflection::method::Descriptor::new(descriptor)?.arguments() {
+        slots.push(next_slot);
+        next_slot += match arg {
+            jreflection::method::Type::Single(jreflection::method::BasicType::Long)     => 2,
+            jreflection::method::Type::Single(jreflection::method::BasicType::Double)   => 2,
+            _                                                                           => 1,
+        };
+    }
+
+    data.skip(4)?; // max_stack, max_locals
+    let code_length = data.u4()? as usize;
+    data.skip(code_length)?;
+    let exception_table_length = data.u2()? as usize;
+    data.skip(8 * exception_table_length)?;
+
+    let mut names = vec![None; slots.len()];
+    for (name, mut data) in read_attributes(data, constants)? {
+        if name != "LocalVariableTable" { continue; }
+        let count = data.u2()? as usize;
+        for _ in 0..count {
+            let start_pc    = data.u2()?;
+            data.skip(2)?; // length
+            let name_index  = data.u2()?;
+            data.skip(2)?; // descriptor_index
+            let slot        = data.u2()?;
+            if start_pc != 0 { continue; } // Not a parameter - parameters are live from the very start of the method
+            if let Some(param) = slots.iter().position(|&s| s == slot) {
+                names[param] = Some(constants.utf8(name_index)?.to_owned());
+            }
+        }
+    }
+    Ok(names)
+}
+
+fn read_attributes<'a, 'c>(reader: &mut Reader<'a>, constants: &'c Constants) -> io::Result<Vec<(&'c str, Reader<'a>)>> {
+    let count = reader.u2()? as usize;
+    let mut attributes = Vec::with_capacity(count);
+    for _ in 0..count {
+        let name    = constants.utf8(reader.u2()?)?;
+        let len     = reader.u4()? as usize;
+        attributes.push((name, Reader(reader.bytes(len)?)));
+    }
+    Ok(attributes)
+}
+
+
+
+/// Just enough of the constant pool to look up `CONSTANT_Utf8` entries by index.
+struct Constants(Vec<Option<String>>);
+
+impl Constants {
+    fn read(reader: &mut Reader) -> io::Result<Self> {
+        let count = reader.u2()? as usize;
+        let mut constants = Vec::with_capacity(count);
+        constants.push(None); // Indicies are 1-based
+        while constants.len() < count {
+            let tag = reader.u1()?;
+            match tag {
+                1 => { // Utf8
+                    let len = reader.u2()? as usize;
+                    constants.push(modified_utf8(reader.bytes(len)?));
+                },
+                3 | 4                   => { reader.skip(4)?; constants.push(None); }, // Integer, Float
+                5 | 6                   => { reader.skip(8)?; constants.push(None); constants.push(None); }, // Long, Double take up two slots
+                7 | 8 | 16 | 19 | 20    => { reader.skip(2)?; constants.push(None); }, // Class, String, MethodType, Module, Package
+                15                      => { reader.skip(3)?; constants.push(None); }, // MethodHandle
+                9 | 10 | 11 | 12 | 17 | 18 => { reader.skip(4)?; constants.push(None); }, // {Field,Method,InterfaceMethod}ref, NameAndType, Dynamic, InvokeDynamic
+                unknown                 => return io_data_err!("Unknown constant pool tag {}", unknown),
+            }
+        }
+        Ok(Self(constants))
+    }
+
+    fn utf8(&self, index: u16) -> io::Result<&str> {
+        match self.0.get(index as usize) {
+            Some(Some(s))   => Ok(s.as_str()),
+            _               => io_data_err!("Constant pool index {} is not a (valid Modified UTF-8) CONSTANT_Utf8", index),
+        }
+    }
+}
+
+/// Decode [Java SE 8 &sect; 4.4.7](https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-4.html#jvms-4.4.7)'s
+/// "Modified UTF-8":  NULs are encoded as two bytes, and supplementary characters as a surrogate pair of three bytes
+/// each.  `None` if invalid - including mispaired surrogates, which no Rust `String` can hold.
+fn modified_utf8(bytes: &[u8]) -> Option<String> {
+    let mut units = Vec::with_capacity(bytes.len());
+    let mut bytes = bytes.iter().cloned();
+    while let Some(b0) = bytes.next() {
+        let mut continuation = || bytes.next().filter(|b| b & 0xC0 == 0x80).map(|b| u16::from(b & 0x3F));
+        units.push(match b0 {
+            0x01..=0x7F => u16::from(b0),
+            0xC0..=0xDF => u16::from(b0 & 0x1F) << 6 | continuation()?,
+            0xE0..=0xEF => u16::from(b0 & 0x0F) << 12 | continuation()? << 6 | continuation()?,
+            _           => return None, // Raw NULs, stray continuation bytes, and 4+ byte sequences aren't Modified UTF-8
+        });
+    }
+    String::from_utf16(&units).ok()
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, n: usize) -> io::Result<&'a [u8]> {
+        if self.0.len() < n { return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Unexpected end of class file")); }
+        let (bytes, rest) = self.0.split_at(n);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    fn skip(&mut self, n: usize) -> io::Result<()> { self.bytes(n).map(|_| ()) }
+    fn u1(&mut self) -> io::Result<u8>  { Ok(self.bytes(1)?[0]) }
+    fn u2(&mut self) -> io::Result<u16> { let b = self.bytes(2)?; Ok(u16::from(b[0]) << 8 | u16::from(b[1])) }
+    fn u4(&mut self) -> io::Result<u32> { let b = self.bytes(4)?; Ok(u32::from(b[0]) << 24 | u32::from(b[1]) << 16 | u32::from(b[2]) << 8 | u32::from(b[3])) }
+}
+
+
+
+#[cfg(test)] fn be(values: &[u16]) -> Vec<u8> { values.iter().flat_map(|value| value.to_be_bytes().to_vec()).collect() }
+
+#[test] fn constant_pool_and_parameter_names() {
+    use crate::util::test_class_file::*;
+
+    let mut constants = Constants::default();
+    constants.long(1);      // Longs and doubles take up two constant pool slots:  miscounting them would throw off
+    constants.double(2.0);  // every index after them.
+    let nul             = constants.modified_utf8(b"a\xC0\x80b");                   // "a\0b"
+    let supplementary   = constants.modified_utf8(b"\xED\xA0\xB5\xED\xB2\xB3");     // U+1D4B3, as a surrogate pair
+    let unpaired        = constants.modified_utf8(b"\xED\xA0\xB5");
+    let [this, a, b, c, local] = ["this", "a", "b", "c", "local"].map(|name| constants.utf8(name));
+    let lvt_attribute   = constants.utf8("LocalVariableTable");
+
+    let method_parameters = |names: &[u16]| -> Vec<u8> {
+        let mut bytes = vec![names.len() as u8];
+        for &name in names { bytes.extend(be(&[name, 0])); } // name_index, access_flags
+        bytes
+    };
+    let code = |locals: &[(u16, u16, u16)]| -> Vec<u8> { // (start_pc, name_index, slot)
+        let mut lvt = be(&[locals.len() as u16]);
+        for &(start_pc, name, slot) in locals { lvt.extend(be(&[start_pc, 8, name, name, slot])); }
+        let mut bytes = be(&[1, 8]); // max_stack, max_locals
+        bytes.extend(&1u32.to_be_bytes());
+        bytes.push(0xB1); // return
+        bytes.extend(be(&[0, 1, lvt_attribute])); // exception_table_length, attributes_count
+        bytes.extend(&(lvt.len() as u32).to_be_bytes());
+        bytes.extend(lvt);
+        bytes
+    };
+
+    let bytes = class_file_with_members(constants, ACC_PUBLIC | ACC_SUPER, "p/Params", Some("java/lang/Object"), &[], &[
+        (ACC_PUBLIC,                "named",    "(ILjava/lang/String;I)V",  vec![("MethodParameters", method_parameters(&[nul, 0, supplementary]))]),
+        (ACC_PUBLIC,                "invalid",  "(I)V",                     vec![("MethodParameters", method_parameters(&[unpaired]))]),
+        (ACC_PUBLIC | ACC_STATIC,   "statik",   "(JDI)V",                   vec![("Code", code(&[(4, local, 4), (0, a, 0), (0, b, 2), (0, c, 4)]))]),
+        (ACC_PUBLIC,                "instance", "(IJLjava/lang/String;)V",  vec![("Code", code(&[(0, this, 0), (0, a, 1), (0, b, 2), (0, c, 4)]))]),
+        (ACC_PUBLIC,                "fallback", "(I)V",                     vec![("MethodParameters", method_parameters(&[0])), ("Code", code(&[(0, a, 1)]))]),
+    ], &[]);
+    let attributes = ClassAttributes::read(&bytes[..]).unwrap();
+    let names = |method: &str, descriptor: &str| attributes.method(method, descriptor).unwrap().parameter_names.iter().map(|name| name.as_deref()).collect::<Vec<_>>();
+
+    assert_eq!(names("named",       "(ILjava/lang/String;I)V"), [Some("a\0b"), None, Some("\u{1D4B3}")]);
+    assert_eq!(names("invalid",     "(I)V"),                    []);
+    assert_eq!(names("statik",      "(JDI)V"),                  [Some("a"), Some("b"), Some("c")]);
+    assert_eq!(names("instance",    "(IJLjava/lang/String;)V"), [Some("a"), Some("b"), Some("c")]);
+    assert_eq!(names("fallback",    "(I)V"),                    [Some("a")]);
+
+    assert_eq!(attributes.warnings.len(), 1, "{:?}", attributes.warnings);
+    assert!(attributes.warnings[0].starts_with("method invalid(I)V: unable to read MethodParameters: "), "{:?}", attributes.warnings);
+}
+
+#[test] fn nullability_annotations() {
+    use crate::util::test_class_file::*;
+
+    let mut constants = Constants::default();
+    let non_null    = constants.utf8("Landroidx/annotation/NonNull;");
+    let nullable    = constants.utf8("Ljavax/annotation/Nullable;");
+    let other       = constants.utf8("Ljava/lang/Deprecated;");
+    let value       = constants.utf8("value");
+
+    // (annotation type, has an element_value pair) - the pair exercises skipping over element values.
+    let annotation = |type_index: u16, with_pair: bool| -> Vec<u8> {
+        if with_pair { let mut bytes = be(&[type_index, 1, value]); bytes.push(b's'); bytes.extend(be(&[value])); bytes }
+        else { be(&[type_index, 0]) }
+    };
+    let annotations = |annotations: &[(u16, bool)]| -> Vec<u8> {
+        let mut bytes = be(&[annotations.len() as u16]);
+        for &(type_index, with_pair) in annotations { bytes.extend(annotation(type_index, with_pair)); }
+        bytes
+    };
+    // (target_type, target_info, path_length, annotation type)
+    let type_annotations = |annotations: &[(u8, &[u8], u8, u16)]| -> Vec<u8> {
+        let mut bytes = be(&[annotations.len() as u16]);
+        for &(target_type, target_info, path_length, type_index) in annotations {
+            bytes.push(target_type);
+            bytes.extend(target_info);
+            bytes.push(path_length);
+            for _ in 0..path_length { bytes.extend(&[0, 0]); } // type_path_kind (array element), type_argument_index
+            bytes.extend(annotation(type_index, true));
+        }
+        bytes
+    };
+
+    let bytes = class_file_with_members(constants, ACC_PUBLIC | ACC_SUPER, "p/Nulls", Some("java/lang/Object"), &[
+        (ACC_PUBLIC, "typed",       "Ljava/lang/String;",   vec![("RuntimeVisibleTypeAnnotations",    type_annotations(&[(0x13, &[], 0, nullable)]))]),
+        (ACC_PUBLIC, "elements",    "[Ljava/lang/String;",  vec![("RuntimeVisibleTypeAnnotations",    type_annotations(&[(0x13, &[], 1, nullable)]))]),
+        (ACC_PUBLIC, "declared",    "Ljava/lang/String;",   vec![("RuntimeInvisibleAnnotations",      annotations(&[(other, true), (non_null, false)]))]),
+    ], &[
+        (ACC_PUBLIC, "returns",     "()Ljava/lang/String;", vec![("RuntimeInvisibleTypeAnnotations",  type_annotations(&[(0x14, &[], 0, non_null)]))]),
+        (ACC_PUBLIC, "parameter",   "(I)Ljava/lang/String;",vec![("RuntimeVisibleTypeAnnotations",    type_annotations(&[(0x16, &[0], 0, nullable), (0x00, &[0], 0, nullable), (0x11, &[0, 0], 0, nullable)]))]),
+        (ACC_PUBLIC, "decl_first",  "()Ljava/lang/String;", vec![
+            ("RuntimeVisibleAnnotations",       annotations(&[(non_null, true)])),
+            ("RuntimeVisibleTypeAnnotations",   type_annotations(&[(0x14, &[], 0, nullable)])),
+        ]),
+        (ACC_PUBLIC, "type_first",  "()Ljava/lang/String;", vec![
+            ("RuntimeVisibleTypeAnnotations",   type_annotations(&[(0x14, &[], 0, nullable)])),
+            ("RuntimeVisibleAnnotations",       annotations(&[(non_null, true)])),
+        ]),
+        (ACC_PUBLIC, "unknown",     "()Ljava/lang/String;", vec![
+            ("RuntimeVisibleAnnotations",       annotations(&[(non_null, false)])),
+            ("RuntimeVisibleTypeAnnotations",   type_annotations(&[(0x40, &[0, 0], 0, nullable)])),
+        ]),
+    ], &[]);
+    let attributes = ClassAttributes::read(&bytes[..]).unwrap();
+    let field   = |name: &str, descriptor: &str| attributes.field(name, descriptor).unwrap().nullability;
+    let method  = |name: &str, descriptor: &str| attributes.method(name, descriptor).unwrap().nullability;
+
+    assert_eq!(field ("typed",      "Ljava/lang/String;"),      Some(Nullability::Nullable));
+    assert_eq!(field ("elements",   "[Ljava/lang/String;"),     None);
+    assert_eq!(field ("declared",   "Ljava/lang/String;"),      Some(Nullability::NonNull));
+    assert_eq!(method("returns",    "()Ljava/lang/String;"),    Some(Nullability::NonNull));
+    assert_eq!(method("parameter",  "(I)Ljava/lang/String;"),   None);
+    assert_eq!(method("decl_first", "()Ljava/lang/String;"),    Some(Nullability::Nullable));
+    assert_eq!(method("type_first", "()Ljava/lang/String;"),    Some(Nullability::Nullable));
+    assert_eq!(method("unknown",    "()Ljava/lang/String;"),    Some(Nullability::NonNull));
+
+    assert_eq!(attributes.warnings.len(), 1, "{:?}", attributes.warnings);
+    assert!(attributes.warnings[0].starts_with("method unknown()Ljava/lang/String;: unable to read RuntimeVisibleTypeAnnotations: "), "{:?}", attributes.warnings);
+}
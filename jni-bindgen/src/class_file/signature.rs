@@ -0,0 +1,206 @@
+/// A parsed generic [ClassSignature](https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-4.html#jvms-4.7.9.1),
+/// e.g. "<E:Ljava/lang/Object;>Ljava/util/AbstractList<TE;>;Ljava/util/List<TE;>;" for `java.util.ArrayList`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClassSignature {
+    pub type_parameters:    Vec<TypeParameter>,
+    pub super_class:        ClassTypeSignature,
+    pub interfaces:         Vec<ClassTypeSignature>,
+}
+
+/// A parsed generic [MethodSignature](https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-4.html#jvms-4.7.9.1),
+/// e.g. "<T:Ljava/lang/Object;>([TT;)[TT;" for `<T> T[] toArray(T[])`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MethodSignature {
+    pub type_parameters:    Vec<TypeParameter>,
+    pub arguments:          Vec<TypeSignature>,
+
+    /// `None` if the method returns `void`.
+    pub return_type:        Option<TypeSignature>,
+}
+
+/// e.g. `T extends Comparable<? super T>` ("T:Ljava/lang/Comparable<-TT;>;")
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TypeParameter {
+    pub name:   String,
+
+    /// The class bound (if any) followed by any interface bounds.
+    pub bounds: Vec<TypeSignature>,
+}
+
+/// A parsed [JavaTypeSignature](https://docs.oracle.com/javase/specs/jvms/se8/html/jvms-4.html#jvms-4.7.9.1).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TypeSignature {
+    /// A primitive type's descriptor character, e.g. 'I' for `int`.
+    Base(char),
+    Class(ClassTypeSignature),
+    Variable(String),
+    Array(Box<TypeSignature>),
+}
+
+/// e.g. `java.util.Map.Entry<K, V>` ("Ljava/util/Map$Entry<TK;TV;>;")
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClassTypeSignature {
+    /// JNI path of the class, e.g. "java/util/Map$Entry".
+    pub path:       String,
+
+    /// Type arguments of the innermost class.  Those of any outer classes are discarded.
+    pub arguments:  Vec<TypeArgument>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TypeArgument {
+    /// `?`
+    Any,
+    /// `T`
+    Exact(TypeSignature),
+    /// `? extends T`
+    Extends(TypeSignature),
+    /// `? super T`
+    Super(TypeSignature),
+}
+
+impl ClassSignature {
+    pub fn parse(signature: &str) -> Option<Self> {
+        let mut parser = Parser(signature);
+        let type_parameters = parser.type_parameters()?;
+        let super_class = parser.class_type()?;
+        let mut interfaces = Vec::new();
+        while !parser.0.is_empty() { interfaces.push(parser.class_type()?); }
+        Some(Self { type_parameters, super_class, interfaces })
+    }
+}
+
+impl MethodSignature {
+    pub fn parse(signature: &str) -> Option<Self> {
+        let mut parser = Parser(signature);
+        let type_parameters = parser.type_parameters()?;
+        parser.expect('(')?;
+        let mut arguments = Vec::new();
+        while !parser.eat(')') { arguments.push(parser.java_type()?); }
+        let return_type = if parser.eat('V') { None } else { Some(parser.java_type()?) };
+        // Ignore ThrowsSignature s
+        Some(Self { type_parameters, arguments, return_type })
+    }
+}
+
+impl TypeSignature {
+    /// Parse a field's signature.
+    pub fn parse(signature: &str) -> Option<Self> {
+        let mut parser = Parser(signature);
+        let ty = parser.java_type()?;
+        if parser.0.is_empty() { Some(ty) } else { None }
+    }
+}
+
+
+
+struct Parser<'a>(&'a str);
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<char> { self.0.chars().next() }
+
+    fn eat(&mut self, ch: char) -> bool {
+        if self.peek() == Some(ch) { self.0 = &self.0[ch.len_utf8()..]; true } else { false }
+    }
+
+    fn expect(&mut self, ch: char) -> Option<()> { if self.eat(ch) { Some(()) } else { None } }
+
+    /// Consume up to (but not including) the first of `terminators`.
+    fn identifier(&mut self, terminators: &[char]) -> Option<&'a str> {
+        let end = self.0.find(|ch| terminators.contains(&ch))?;
+        let (id, rest) = self.0.split_at(end);
+        self.0 = rest;
+        if id.is_empty() { None } else { Some(id) }
+    }
+
+    fn type_parameters(&mut self) -> Option<Vec<TypeParameter>> {
+        let mut type_parameters = Vec::new();
+        if !self.eat('<') { return Some(type_parameters); }
+        while !self.eat('>') {
+            let name = self.identifier(&[':'])?.to_owned();
+            let mut bounds = Vec::new();
+            while self.eat(':') {
+                if self.peek() == Some(':') { continue; } // No class bound, only interface bounds
+                bounds.push(self.java_type()?);
+            }
+            type_parameters.push(TypeParameter { name, bounds });
+        }
+        Some(type_parameters)
+    }
+
+    fn java_type(&mut self) -> Option<TypeSignature> {
+        match self.peek()? {
+            'L' => Some(TypeSignature::Class(self.class_type()?)),
+            'T' => {
+                self.expect('T')?;
+                let name = self.identifier(&[';'])?.to_owned();
+                self.expect(';')?;
+                Some(TypeSignature::Variable(name))
+            },
+            '[' => {
+                self.expect('[')?;
+                Some(TypeSignature::Array(Box::new(self.java_type()?)))
+            },
+            ch @ 'B' | ch @ 'C' | ch @ 'D' | ch @ 'F' | ch @ 'I' | ch @ 'J' | ch @ 'S' | ch @ 'Z' => {
+                self.expect(ch)?;
+                Some(TypeSignature::Base(ch))
+            },
+            _ => None,
+        }
+    }
+
+    fn class_type(&mut self) -> Option<ClassTypeSignature> {
+        self.expect('L')?;
+        let mut path = self.identifier(&['<', '.', ';'])?.to_owned();
+        let mut arguments = self.type_arguments()?;
+        while self.eat('.') {
+            path.push('$');
+            path.push_str(self.identifier(&['<', '.', ';'])?);
+            arguments = self.type_arguments()?;
+        }
+        self.expect(';')?;
+        Some(ClassTypeSignature { path, arguments })
+    }
+
+    fn type_arguments(&mut self) -> Option<Vec<TypeArgument>> {
+        let mut arguments = Vec::new();
+        if !self.eat('<') { return Some(arguments); }
+        while !self.eat('>') {
+            arguments.push(if self.eat('*') {
+                TypeArgument::Any
+            } else if self.eat('+') {
+                TypeArgument::Extends(self.java_type()?)
+            } else if self.eat('-') {
+                TypeArgument::Super(self.java_type()?)
+            } else {
+                TypeArgument::Exact(self.java_type()?)
+            });
+        }
+        Some(arguments)
+    }
+}
+
+#[test] fn signature_parse_test() {
+    let class = |path: &str, arguments: Vec<TypeArgument>| TypeSignature::Class(ClassTypeSignature { path: path.to_owned(), arguments });
+    let var = |name: &str| TypeSignature::Variable(name.to_owned());
+
+    let array_list = ClassSignature::parse("<E:Ljava/lang/Object;>Ljava/util/AbstractList<TE;>;Ljava/util/List<TE;>;Ljava/io/Serializable;").unwrap();
+    assert_eq!(array_list.type_parameters, vec![TypeParameter { name: "E".to_owned(), bounds: vec![class("java/lang/Object", vec![])] }]);
+    assert_eq!(array_list.super_class.path, "java/util/AbstractList");
+    assert_eq!(array_list.super_class.arguments, vec![TypeArgument::Exact(var("E"))]);
+    assert_eq!(array_list.interfaces.len(), 2);
+    assert_eq!(array_list.interfaces[1].path, "java/io/Serializable");
+
+    let sort = MethodSignature::parse("<T::Ljava/lang/Comparable<-TT;>;>(Ljava/util/List<TT;>;)V").unwrap();
+    assert_eq!(sort.type_parameters, vec![TypeParameter { name: "T".to_owned(), bounds: vec![class("java/lang/Comparable", vec![TypeArgument::Super(var("T"))])] }]);
+    assert_eq!(sort.arguments, vec![class("java/util/List", vec![TypeArgument::Exact(var("T"))])]);
+    assert_eq!(sort.return_type, None);
+
+    let to_array = MethodSignature::parse("<T:Ljava/lang/Object;>([TT;I)[TT;^Ljava/io/IOException;").unwrap();
+    assert_eq!(to_array.arguments, vec![TypeSignature::Array(Box::new(var("T"))), TypeSignature::Base('I')]);
+    assert_eq!(to_array.return_type, Some(TypeSignature::Array(Box::new(var("T")))));
+
+    assert_eq!(TypeSignature::parse("Ljava/util/Map<TK;TV;>.Entry<TK;*>;"), Some(class("java/util/Map$Entry", vec![TypeArgument::Exact(var("K")), TypeArgument::Any])));
+    assert_eq!(TypeSignature::parse("Ljava/util/List<+Ljava/lang/Number;>;"), Some(class("java/util/List", vec![TypeArgument::Extends(class("java/lang/Number", vec![]))])));
+    assert_eq!(TypeSignature::parse("Ljava/util/List<TT;"), None);
+}
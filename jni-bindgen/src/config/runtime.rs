@@ -2,6 +2,8 @@
 
 use crate::config::*;
 
+use regex::{Regex, RegexSet};
+
 use std::collections::*;
 use std::ffi::*;
 use std::path::*;
@@ -45,19 +47,22 @@ pub struct Config {
     pub(crate) codegen:                     toml::CodeGen,
     pub(crate) doc_patterns:                Vec<DocPattern>,
     pub(crate) input_files:                 Vec<PathBuf>,
+    pub(crate) input_classpath:             Vec<PathBuf>,
     pub(crate) output_path:                 PathBuf,
     pub(crate) output_dir:                  PathBuf,
     pub(crate) logging_verbose:             bool,
 
-    pub(crate) ignore_classes:              HashSet<String>,
-    pub(crate) ignore_class_fields:         HashSet<String>,
-    pub(crate) ignore_class_methods:        HashSet<String>,
-    pub(crate) ignore_class_method_sigs:    HashSet<String>,
+    pub(crate) includes:                    Rules<usize>,
+
+    pub(crate) ignore_classes:              Rules<()>,
+    pub(crate) ignore_class_fields:         Rules<()>,
+    pub(crate) ignore_class_methods:        Rules<()>,
 
-    pub(crate) rename_classes:              HashMap<String, String>,
-    pub(crate) rename_class_fields:         HashMap<String, String>,
-    pub(crate) rename_class_methods:        HashMap<String, String>,
-    pub(crate) rename_class_method_sigs:    HashMap<String, String>,
+    pub(crate) rename_classes:              Rules<String>,
+    pub(crate) rename_class_fields:         Rules<String>,
+    pub(crate) rename_class_methods:        Rules<String>,
+
+    pub(crate) externs:                     Vec<toml::Extern>,
 }
 
 impl From<toml::FileWithContext> for Config {
@@ -68,43 +73,38 @@ impl From<toml::FileWithContext> for Config {
         let documentation   = file.documentation;
         let logging         = file.logging;
 
-        let mut ignore_classes              = HashSet::new();
-        let mut ignore_class_fields         = HashSet::new();
-        let mut ignore_class_methods        = HashSet::new();
-        let mut ignore_class_method_sigs    = HashSet::new();
-        for ignore in file.ignores {
-            // TODO: Warn if sig && !method
-            // TODO: Warn if field && method
-            if let Some(method) = ignore.method.as_ref() {
-                if let Some(sig) = ignore.signature.as_ref() {
-                    ignore_class_method_sigs.insert(format!("{}\x1f{}\x1f{}", ignore.class, method, sig));
-                } else {
-                    ignore_class_methods.insert(format!("{}\x1f{}", ignore.class, method));
-                }
-            } else if let Some(field) = ignore.field.as_ref() {
-                ignore_class_fields.insert(format!("{}\x1f{}", ignore.class, field));
+        let includes = Rules::new(file.includes.iter().map(|include| {
+            let key = RuleKey::new(include.regex, &include.class, None, None, None, || include.key_regex()).unwrap_or_else(|err| panic!("Invalid [[include]] pattern: {}", err));
+            (key, include.depth.unwrap_or(std::usize::MAX))
+        }).collect());
+
+        let mut ignore_classes              = Vec::new();
+        let mut ignore_class_fields         = Vec::new();
+        let mut ignore_class_methods        = Vec::new();
+        for ignore in file.ignores.iter() {
+            let key = RuleKey::new(ignore.regex, &ignore.class, ignore.field.as_ref(), ignore.method.as_ref(), ignore.signature.as_ref(), || ignore.key_regex());
+            let key = key.unwrap_or_else(|err| panic!("Invalid [[ignore]] pattern: {}", err));
+            if ignore.method.is_some() {
+                ignore_class_methods.push((key, ()));
+            } else if ignore.field.is_some() {
+                ignore_class_fields.push((key, ()));
             } else {
-                ignore_classes.insert(ignore.class.clone());
+                ignore_classes.push((key, ()));
             }
         }
 
-        let mut rename_classes              = HashMap::new();
-        let mut rename_class_fields         = HashMap::new();
-        let mut rename_class_methods        = HashMap::new();
-        let mut rename_class_method_sigs    = HashMap::new();
-        for rename in file.renames {
-            // TODO: Warn if sig && !method
-            // TODO: Warn if field && method
-            if let Some(method) = rename.method.as_ref() {
-                if let Some(sig) = rename.signature.as_ref() {
-                    rename_class_method_sigs.insert(format!("{}\x1f{}\x1f{}", rename.class, method, sig), rename.to.clone());
-                } else {
-                    rename_class_methods.insert(format!("{}\x1f{}", rename.class, method), rename.to.clone());
-                }
-            } else if let Some(field) = rename.field.as_ref() {
-                rename_class_fields.insert(format!("{}\x1f{}", rename.class, field), rename.to.clone());
+        let mut rename_classes              = Vec::new();
+        let mut rename_class_fields         = Vec::new();
+        let mut rename_class_methods        = Vec::new();
+        for rename in file.renames.iter() {
+            let key = RuleKey::new(rename.regex, &rename.class, rename.field.as_ref(), rename.method.as_ref(), rename.signature.as_ref(), || rename.key_regex());
+            let key = key.unwrap_or_else(|err| panic!("Invalid [[rename]] pattern: {}", err));
+            if rename.method.is_some() {
+                rename_class_methods.push((key, rename.to.clone()));
+            } else if rename.field.is_some() {
+                rename_class_fields.push((key, rename.to.clone()));
             } else {
-                rename_classes.insert(rename.class.clone(), rename.to.clone());
+                rename_classes.push((key, rename.to.clone()));
             }
         }
 
@@ -119,17 +119,128 @@ impl From<toml::FileWithContext> for Config {
             codegen:                file.codegen.clone(),
             doc_patterns:           documentation.patterns.into_iter().map(|pat| pat.into()).collect(),
             input_files:            file.input.files.into_iter().map(|file| resolve_file(file, &dir)).collect(),
+            input_classpath:        file.input.classpath.into_iter().map(|file| resolve_file(file, &dir)).collect(),
             output_path,
             output_dir,
             logging_verbose:        logging.verbose,
-            ignore_classes,
-            ignore_class_fields,
-            ignore_class_methods,
-            ignore_class_method_sigs,
-            rename_classes,
-            rename_class_fields,
-            rename_class_methods,
-            rename_class_method_sigs,
+            includes,
+            ignore_classes:         Rules::new(ignore_classes),
+            ignore_class_fields:    Rules::new(ignore_class_fields),
+            ignore_class_methods:   Rules::new(ignore_class_methods),
+            rename_classes:         Rules::new(rename_classes),
+            rename_class_fields:    Rules::new(rename_class_fields),
+            rename_class_methods:   Rules::new(rename_class_methods),
+            externs:                file.externs,
+        }
+    }
+}
+
+impl Config {
+    /// How many levels of referenced types to generate bindings for, starting from `class`, if it's \[[include\]]d.
+    pub(crate) fn include_depth(&self, class: &str) -> Option<usize> {
+        self.includes.matching(class, None).into_iter().map(|rule| self.includes.values[rule]).max()
+    }
+
+    pub(crate) fn ignore_class(&self, class: &str) -> bool {
+        !self.ignore_classes.matching(class, None).is_empty()
+    }
+
+    pub(crate) fn ignore_class_field(&self, class: &str, field: &str) -> bool {
+        !self.ignore_class_fields.matching(&format!("{}\x1f{}", class, field), None).is_empty()
+    }
+
+    pub(crate) fn ignore_class_method(&self, class: &str, method: &str, signature: &str) -> bool {
+        let any_signature = format!("{}\x1f{}", class, method);
+        !self.ignore_class_methods.matching(&format!("{}\x1f{}", any_signature, signature), Some(&any_signature)).is_empty()
+    }
+
+    pub(crate) fn rename_class(&self, class: &str) -> Option<String> {
+        self.rename_classes.rename(class, None)
+    }
+
+    pub(crate) fn rename_class_field(&self, class: &str, field: &str) -> Option<String> {
+        self.rename_class_fields.rename(&format!("{}\x1f{}", class, field), None)
+    }
+
+    pub(crate) fn rename_class_method(&self, class: &str, method: &str, signature: &str) -> Option<String> {
+        let any_signature = format!("{}\x1f{}", class, method);
+        self.rename_class_methods.rename(&format!("{}\x1f{}", any_signature, signature), Some(&any_signature))
+    }
+}
+
+/// How a rule matches `"class"`, `"class\x1ffield"`, or `"class\x1fmethod\x1fsignature"` keys.
+enum RuleKey {
+    /// No wildcards:  matches this key only.  Method rules without a signature omit it (`"class\x1fmethod"`.)
+    Exact(String),
+
+    /// Wildcards or `regex = true`.
+    Pattern(Regex),
+}
+
+impl RuleKey {
+    fn new(regex: bool, class: &str, field: Option<&String>, method: Option<&String>, signature: Option<&String>, key_regex: impl FnOnce() -> Result<Regex, regex::Error>) -> Result<Self, regex::Error> {
+        let components = || std::iter::once(class).chain(method.or(field).map(|s| s.as_str())).chain(method.and(signature).map(|s| s.as_str()));
+        if regex || components().any(|component| component.contains(['*', '?'])) {
+            Ok(RuleKey::Pattern(key_regex()?))
+        } else {
+            Ok(RuleKey::Exact(components().collect::<Vec<_>>().join("\x1f")))
+        }
+    }
+}
+
+/// One kind of \[[ignore\]], \[[rename\]], or \[[include\]] rule, in declaration order.  Exact rules are looked up by
+/// key in O(1) - only wildcard and regex rules are matched against, all at once via a RegexSet.
+pub(crate) struct Rules<T> {
+    values:     Vec<T>,
+    exact:      HashMap<String, Vec<usize>>,
+    patterns:   Vec<(usize, Regex)>,
+    set:        RegexSet,
+}
+
+impl<T> Rules<T> {
+    fn new(rules: Vec<(RuleKey, T)>) -> Self {
+        let mut values      = Vec::new();
+        let mut exact       = HashMap::new();
+        let mut patterns    = Vec::new();
+        for (index, (key, value)) in rules.into_iter().enumerate() {
+            values.push(value);
+            match key {
+                RuleKey::Exact(key)     => exact.entry(key).or_insert_with(Vec::new).push(index),
+                RuleKey::Pattern(regex) => patterns.push((index, regex)),
+            }
+        }
+        let set = RegexSet::new(patterns.iter().map(|(_, regex)| regex.as_str())).expect("RegexSet of already valid Regexs");
+        Self { values, exact, patterns, set }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool { self.values.is_empty() }
+
+    /// The indices of every rule matching `key` - or exactly matching `any_signature_key` - in declaration order.
+    fn matching(&self, key: &str, any_signature_key: Option<&str>) -> Vec<usize> {
+        let mut matching = Vec::new();
+        for key in std::iter::once(key).chain(any_signature_key) {
+            matching.extend(self.exact.get(key).into_iter().flatten().cloned());
+        }
+        if !self.patterns.is_empty() {
+            matching.extend(self.set.matches(key).into_iter().map(|pattern| self.patterns[pattern].0));
+        }
+        matching.sort();
+        matching
+    }
+}
+
+impl Rules<String> {
+    /// The first matching [[rename]]'s `to` - with `$1`, `${name}` etc. replaced by captures if it's a pattern.
+    fn rename(&self, key: &str, any_signature_key: Option<&str>) -> Option<String> {
+        let rule = *self.matching(key, any_signature_key).first()?;
+        let to = &self.values[rule];
+        match self.patterns.iter().find(|(index, _)| *index == rule) {
+            None => Some(to.clone()),
+            Some((_, regex)) => {
+                let mut renamed = String::new();
+                regex.captures(key)?.expand(to, &mut renamed);
+                Some(renamed)
+            },
         }
     }
 }
@@ -166,3 +277,56 @@ fn expand_vars(string: String) -> String {
     assert!(expanding, "Uneven number of %s in path: {:?}, would mis-expand into: {:?}", &string, &buf);
     buf
 }
+
+#[test] fn exact_and_pattern_rules() {
+    let file = toml::File::read_str(r#"
+        [input]
+        files = ["android.jar"]
+
+        [output]
+        path = "android28.rs"
+
+        [[include]]
+        class = "java/lang/*"
+        depth = 1
+
+        [[include]]
+        class = "java/lang/String"
+        depth = 2
+
+        [[ignore]]
+        class = "java/io/DataOutput"
+        method = "write"
+
+        [[ignore]]
+        class = "java/io/*Stream"
+        method = "write"
+        signature = "(I)V"
+
+        [[rename]]
+        class = "java/net/*"
+        method = "getHeaderField"
+        to = "getHeaderField_$1"
+
+        [[rename]]
+        class = "java/net/URLConnection"
+        method = "getHeaderField"
+        signature = "(I)Ljava/lang/String;"
+        to = "getHeaderFieldByIndex"
+    "#).unwrap();
+    let config = Config::from(toml::FileWithContext { file, directory: PathBuf::new() });
+
+    assert_eq!(config.include_depth("java/lang/Object"),    Some(1));
+    assert_eq!(config.include_depth("java/lang/String"),    Some(2)); // Deepest match wins
+    assert_eq!(config.include_depth("java/util/List"),      None);
+
+    assert!( config.ignore_class_method("java/io/DataOutput",       "write", "([B)V")); // Exact, any signature
+    assert!( config.ignore_class_method("java/io/OutputStream",     "write", "(I)V"));
+    assert!(!config.ignore_class_method("java/io/OutputStream",     "write", "([B)V"));
+    assert!(!config.ignore_class_method("java/io/DataOutputStream", "read",  "(I)V"));
+
+    // The first declared rename wins, whether exact or not
+    assert_eq!(config.rename_class_method("java/net/URLConnection", "getHeaderField", "(I)Ljava/lang/String;").as_deref(), Some("getHeaderField_URLConnection"));
+    assert_eq!(config.rename_class_method("java/net/URL",           "getHeaderField", "()V").as_deref(),                   Some("getHeaderField_URL"));
+    assert_eq!(config.rename_class_method("java/io/File",           "getHeaderField", "()V"),                               None);
+}
@@ -3,6 +3,7 @@
 use crate::identifiers::MethodManglingStyle;
 use crate::identifiers::FieldManglingStyle;
 
+use regex::Regex;
 use serde_derive::*;
 
 use std::fs;
@@ -12,16 +13,18 @@ use std::path::*;
 
 
 /// How should the JNIEnv be passed around.
-/// 
-/// In the future, I plan to support an "Implicit" mode for platforms like Android - where there's at most one JVM at a
-/// time - and said JVM lives for the lifetime of the application - so we can save some boilerplate for end users by
-/// hiding it's use as an implementation detail.
 #[serde(rename_all = "snake_case")]
 #[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
 pub enum StaticEnvStyle {
     /// All static methods, static field getters, global hydration, etc. requires an explicit env parameter.
     Explicit,
 
+    /// Static methods, constructors, and static field accessors don't take an env parameter, instead using the current
+    /// thread's env for the only loaded JVM - for platforms like Android, where there's at most one JVM at a time, and
+    /// said JVM lives for the lifetime of the application.  Since there's no caller env to tie their lifetime to, these
+    /// return `Global`s instead of `Local`s.
+    Implicit,
+
     #[doc(hidden)] __NonExhaustive,
 }
 
@@ -46,6 +49,24 @@ impl Default for CodeShardingStyle {
     fn default() -> Self { CodeShardingStyle::PerClass }
 }
 
+/// How should Java generics be represented.
+#[serde(rename_all = "snake_case")]
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+pub enum GenericsStyle {
+    /// Ignore generic `Signature`s entirely, binding only the erased types (`List.get` returns `Local<Object>`.)
+    Erased,
+
+    /// Generic classes become structs with phantom type parameters (e.g. `List<E>`), and type variables in generic
+    /// `Signature`s are used for argument, return, and field types (`List<String>.get` returns `Local<String>`.)
+    Phantom,
+
+    #[doc(hidden)] __NonExhaustive,
+}
+
+impl Default for GenericsStyle {
+    fn default() -> Self { GenericsStyle::Erased }
+}
+
 fn default_throwable_type() -> String { "jni_android_sys::java::lang::Throwable".to_owned() }
 fn default_true() -> bool { true }
 fn default_method_naming_style() -> MethodManglingStyle { MethodManglingStyle::Rustify }
@@ -85,6 +106,20 @@ pub struct CodeGen {
     /// Should not-emitted methods/fields still generate their code commented out?
     #[serde(default = "default_true")]
     pub keep_rejected_emits: bool,
+
+    /// Should Java `native` methods generate a Rust trait to implement them with, plus a macro to export said
+    /// implementation as `Java_...` symbols?
+    #[serde(default = "Default::default")]
+    pub native_traits: bool,
+
+    /// How should Java generics be represented?
+    #[serde(default = "Default::default")]
+    pub generics: GenericsStyle,
+
+    /// Should methods annotated `@NonNull` return `Local<T>` instead of `Option<Local<T>>`?  If they return null anyways,
+    /// they'll return a `java.lang.NullPointerException` as an error instead.
+    #[serde(default = "Default::default")]
+    pub nullability_annotations: bool,
 }
 
 impl Default for CodeGen {
@@ -98,6 +133,9 @@ impl Default for CodeGen {
             shard_structs:                  true,
             feature_per_struct:             true,
             keep_rejected_emits:            true,
+            native_traits:                  false,
+            generics:                       Default::default(),
+            nullability_annotations:        false,
         }
     }
 }
@@ -199,10 +237,17 @@ pub struct Documentation {
 /// The \[input\] section.
 #[derive(Debug, Clone, Deserialize, Default)]
 pub struct Input {
-    /// `.jar` or `.class` files to scan for JVM class info.
+    /// `.jar`, `.jmod`, `.aar`, or `.class` files to scan for JVM class info.  Directories are scanned recursively for
+    /// `.class` files (e.g. `build/classes/java/main` or an exploded module image), unless they're a JDK image, in which
+    /// case their `jmods/*.jmod` are scanned instead.  `.aar`s have their `classes.jar` and `libs/*.jar` scanned.
     /// 
     /// May in the future add support for `.apk`s, `.aab`s, etc.
     pub files: Vec<PathBuf>,
+
+    /// Like `files`, but only read to resolve supertypes and other referenced types - no bindings are generated for
+    /// these classes, and methods/fields referencing them are rejected.
+    #[serde(default = "Vec::new")]
+    pub classpath: Vec<PathBuf>,
 }
 
 /// The \[output\] section.
@@ -220,15 +265,50 @@ pub struct Logging {
 }
 
 /// An \[[ignore\]] section.
+///
+/// `class`, `field`, `method`, and `signature` are matched exactly, unless they contain `*` (any run of characters,
+/// including `/`) or `?` (any single character) wildcards - or `regex = true`, in which case they're (anchored) regular
+/// expressions instead.  Omitting `signature` matches every overload of `method`.
 #[derive(Debug, Clone, Deserialize, Default)]
 pub struct Ignore {
     pub class:     String,
     pub field:     Option<String>,
     pub method:    Option<String>,
     pub signature: Option<String>,
+
+    /// Treat `class`, `field`, `method`, and `signature` as regular expressions instead of wildcard patterns.
+    #[serde(default = "Default::default")]
+    pub regex:     bool,
+}
+
+impl Ignore {
+    /// A regex matching the `"class"`, `"class\x1ffield"`, or `"class\x1fmethod\x1fsignature"` keys this ignores.
+    pub(crate) fn key_regex(&self) -> Result<Regex, regex::Error> {
+        key_regex(self.regex, &self.class, self.field.as_ref(), self.method.as_ref(), self.signature.as_ref())
+    }
 }
 
 /// A \[[rename\]] section.
+///
+/// Matched the same way as [Ignore].  Wildcards and regex groups capture, numbered from left to right across `class`,
+/// `field`/`method`, and `signature` - `to` can refer to these as `$1`, `${2}`, or `${name}`.  When several renames
+/// match, the first one declared wins.
+///
+/// ```toml
+/// [[rename]]
+/// class     = "java/io/*"
+/// method    = "write"
+/// signature = "(I)V"
+/// to        = "write_int"
+///
+/// [[rename]]
+/// class     = "android/view/.*"
+/// method    = "(get|set)Tag"
+/// regex     = true
+/// to        = "${1}_view_tag"
+/// ```
+///
+/// [Ignore]:   struct.Ignore.html
 #[derive(Debug, Clone, Deserialize, Default)]
 pub struct Rename {
     pub to:         String,
@@ -236,6 +316,102 @@ pub struct Rename {
     pub field:      Option<String>,
     pub method:     Option<String>,
     pub signature:  Option<String>,
+
+    /// Treat `class`, `field`, `method`, and `signature` as regular expressions instead of wildcard patterns.
+    #[serde(default = "Default::default")]
+    pub regex:      bool,
+}
+
+impl Rename {
+    /// A regex matching the `"class"`, `"class\x1ffield"`, or `"class\x1fmethod\x1fsignature"` keys this renames.
+    pub(crate) fn key_regex(&self) -> Result<Regex, regex::Error> {
+        key_regex(self.regex, &self.class, self.field.as_ref(), self.method.as_ref(), self.signature.as_ref())
+    }
+}
+
+/// An \[[include\]] section.  If any are present, bindings are only generated for matching classes, plus the classes
+/// they reference - supertypes, interfaces, and public method parameter, return, and field types - transitively.
+///
+/// `class` is matched the same way as [Ignore].  Generated code always refers to `throwable_type`, so you'll want to
+/// include that class too.
+///
+/// ```toml
+/// [[include]]
+/// class = "java/lang/Throwable"
+/// depth = 0
+///
+/// [[include]]
+/// class = "android/app/Activity"
+///
+/// [[include]]
+/// class = "android/widget/*"
+/// depth = 1
+/// ```
+///
+/// [Ignore]:   struct.Ignore.html
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct Include {
+    pub class:      String,
+
+    /// Treat `class` as a regular expression instead of a wildcard pattern.
+    #[serde(default = "Default::default")]
+    pub regex:      bool,
+
+    /// How many levels of parameter, return, and field types to follow from matching classes.  Types beyond this
+    /// depth get no bindings, so members referencing them are rejected.  Supertypes and interfaces don't count
+    /// against this limit.  Unlimited by default.
+    pub depth:      Option<usize>,
+}
+
+impl Include {
+    /// A regex matching the `"class"` keys this includes.
+    pub(crate) fn key_regex(&self) -> Result<Regex, regex::Error> {
+        key_regex(self.regex, &self.class, None, None, None)
+    }
+}
+
+fn key_regex(regex: bool, class: &str, field: Option<&String>, method: Option<&String>, signature: Option<&String>) -> Result<Regex, regex::Error> {
+    let component = |pattern: &str| -> String {
+        if regex { return format!("(?:{})", pattern); }
+        let mut buf = String::new();
+        for ch in pattern.chars() {
+            match ch {
+                '*' => buf.push_str("([^\\x1f]*)"),
+                '?' => buf.push_str("([^\\x1f])"),
+                ch  => buf.push_str(&regex::escape(ch.encode_utf8(&mut [0; 4]))),
+            }
+        }
+        buf
+    };
+
+    // TODO: Warn if sig && !method
+    // TODO: Warn if field && method
+    let key = if let Some(method) = method {
+        let signature = signature.map_or_else(|| String::from("[^\\x1f]*"), |sig| component(sig));
+        format!("^{}\\x1f{}\\x1f{}$", component(class), component(method), signature)
+    } else if let Some(field) = field {
+        format!("^{}\\x1f{}$", component(class), component(field))
+    } else {
+        format!("^{}$", component(class))
+    };
+    Regex::new(&key)
+}
+
+/// An \[[extern\]] section.
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct Extern {
+    /// What java class(es) to map onto an existing crate.  This takes the form of a simple prefix to a JNI path with no
+    /// wildcards, such as "android/" or "java/".  The first matching \[[extern\]] wins.
+    pub jni_prefix: String,
+
+    /// The Rust path to use in place of `crate::` for matching classes, such as "jni_android_sys::" - mapping
+    /// "android/app/Activity" to "jni_android_sys::android::app::Activity".  No bindings are generated for them.
+    pub rust_prefix: String,
+
+    /// The existing crate's name, as used in `Cargo.toml` features, such as "jni-android-sys".  If set, referencing a
+    /// matching class requires a feature of the same name as usual, which enables said crate's feature for the class.
+    /// If not set, references to matching classes aren't feature gated.
+    pub feature_crate: Option<String>,
 }
 
 /// Format for a `jni-bindgen.toml` file or in-memory settings.
@@ -252,6 +428,9 @@ pub struct Rename {
 /// static_env                      = "implicit"
 /// method_naming_style             = "java"
 /// method_naming_style_collision   = "rustify_long_signature"
+/// native_traits                   = true
+/// generics                        = "phantom"
+/// nullability_annotations         = true
 /// 
 /// [logging]
 /// verbose = true
@@ -273,12 +452,24 @@ pub struct Rename {
 /// files = [
 ///     "%LOCALAPPDATA%/Android/Sdk/platforms/android-28/android.jar"
 /// ]
+/// classpath = [
+///     "%LOCALAPPDATA%/Android/Sdk/extras/android/m2repository/com/android/support/support-annotations/28.0.0/support-annotations-28.0.0.jar"
+/// ]
 /// 
 /// [output]
 /// path = "android28.rs"
 /// 
 /// 
 /// 
+/// [[include]]
+/// class = "some/java/*"
+///
+/// [[include]]
+/// class = "some/other/java/Class"
+/// depth = 2
+/// 
+/// 
+/// 
 /// [[ignore]]
 /// class = "some/java/Class"
 /// 
@@ -307,6 +498,13 @@ pub struct Rename {
 /// method    = "someOtherMethod"
 /// signature = "()V"
 /// to        = "some_other_method"
+///
+///
+///
+/// [[extern]]
+/// jni_prefix      = "android/"
+/// rust_prefix     = "jni_android_sys::"
+/// feature_crate   = "jni-android-sys"
 /// ```
 #[derive(Debug, Clone, Deserialize)]
 pub struct File {
@@ -327,6 +525,10 @@ pub struct File {
     /// Output(s) from the jni-bindgen process.
     pub output: Output,
 
+    /// Classes to generate bindings for (plus their dependencies.)  If empty, all classes get bindings.
+    #[serde(rename = "include")] #[serde(default = "Vec::new")]
+    pub includes: Vec<Include>,
+
     /// Classes and class methods to ignore.
     #[serde(rename = "ignore")] #[serde(default = "Vec::new")]
     pub ignores: Vec<Ignore>,
@@ -334,6 +536,10 @@ pub struct File {
     /// Classes and class methods to rename.
     #[serde(rename = "rename")] #[serde(default = "Vec::new")]
     pub renames: Vec<Rename>,
+
+    /// Classes to map onto existing crates instead of generating bindings for.
+    #[serde(rename = "extern")] #[serde(default = "Vec::new")]
+    pub externs: Vec<Extern>,
 }
 
 impl File {
@@ -347,6 +553,9 @@ impl File {
     /// Read from a memory buffer, under the assumption that it's in the "jni-bindgen.toml" file format.
     pub fn read_str(buffer: &str) -> io::Result<Self> {
         let file : File = toml::from_str(buffer).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        for include in file.includes.iter() { include.key_regex().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?; }
+        for ignore in file.ignores.iter() { ignore.key_regex().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?; }
+        for rename in file.renames.iter() { rename.key_regex().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?; }
         Ok(file)
     }
 
@@ -388,6 +597,9 @@ impl File {
         static_env                      = "explicit"
         method_naming_style             = "java"
         method_naming_style_collision   = "rustify_long_signature"
+        native_traits                   = true
+        generics                        = "phantom"
+        nullability_annotations         = true
 
         [logging]
         verbose = true
@@ -408,12 +620,24 @@ impl File {
         files = [
             "%LOCALAPPDATA%/Android/Sdk/platforms/android-28/android.jar"
         ]
+        classpath = [
+            "libs/dependency.jar"
+        ]
 
         [output]
         path = "android28.rs"
 
 
 
+        [[include]]
+        class = "some/java/*"
+
+        [[include]]
+        class = "some/other/java/Class"
+        depth = 2
+
+
+
         [[ignore]]
         class = "some/java/Class"
 
@@ -442,12 +666,26 @@ impl File {
         method    = "someOtherMethod"
         signature = "()V"
         to        = "some_other_method"
+
+
+
+        [[extern]]
+        jni_prefix      = "android/"
+        rust_prefix     = "jni_android_sys::"
+        feature_crate   = "jni-android-sys"
+
+        [[extern]]
+        jni_prefix      = "java/"
+        rust_prefix     = "jni_android_sys::"
     "#;
     let file = File::read_str(well_configured_toml).unwrap();
 
     assert_eq!(file.codegen.static_env,                     StaticEnvStyle::Explicit);
     assert_eq!(file.codegen.method_naming_style,            MethodManglingStyle::Java);
     assert_eq!(file.codegen.method_naming_style_collision,  MethodManglingStyle::RustifyLongSignature);
+    assert_eq!(file.codegen.native_traits,                  true);
+    assert_eq!(file.codegen.generics,                       GenericsStyle::Phantom);
+    assert_eq!(file.codegen.nullability_annotations,        true);
 
     assert_eq!(file.logging.verbose, true);
 
@@ -470,8 +708,17 @@ impl File {
     assert_eq!(file.documentation.patterns[1].argument_seperator,               ",");
 
     assert_eq!(file.input.files, &[Path::new("%LOCALAPPDATA%/Android/Sdk/platforms/android-28/android.jar")]);
+    assert_eq!(file.input.classpath, &[Path::new("libs/dependency.jar")]);
     assert_eq!(file.output.path, Path::new("android28.rs"));
 
+    assert_eq!(file.includes.len(), 2);
+
+    assert_eq!(file.includes[0].class,     "some/java/*");
+    assert_eq!(file.includes[0].depth,     None);
+
+    assert_eq!(file.includes[1].class,     "some/other/java/Class");
+    assert_eq!(file.includes[1].depth,     Some(2));
+
     assert_eq!(file.ignores.len(), 3);
 
     assert_eq!(file.ignores[0].class,      "some/java/Class");
@@ -502,6 +749,16 @@ impl File {
     assert_eq!(file.renames[2].method,     Some("someOtherMethod".to_owned()));
     assert_eq!(file.renames[2].signature,  Some("()V".to_owned()));
     assert_eq!(file.renames[2].to,         "some_other_method");
+
+    assert_eq!(file.externs.len(), 2);
+
+    assert_eq!(file.externs[0].jni_prefix,      "android/");
+    assert_eq!(file.externs[0].rust_prefix,     "jni_android_sys::");
+    assert_eq!(file.externs[0].feature_crate,   Some("jni-android-sys".to_owned()));
+
+    assert_eq!(file.externs[1].jni_prefix,      "java/");
+    assert_eq!(file.externs[1].rust_prefix,     "jni_android_sys::");
+    assert_eq!(file.externs[1].feature_crate,   None);
 }
 
 #[test] fn load_minimal_toml() {
@@ -517,13 +774,88 @@ impl File {
     assert_eq!(file.codegen.static_env,                     StaticEnvStyle::Explicit);
     assert_eq!(file.codegen.method_naming_style,            MethodManglingStyle::Rustify);
     assert_eq!(file.codegen.method_naming_style_collision,  MethodManglingStyle::RustifyShortSignature);
+    assert_eq!(file.codegen.native_traits,                  false);
+    assert_eq!(file.codegen.generics,                       GenericsStyle::Erased);
+    assert_eq!(file.codegen.nullability_annotations,        false);
 
     assert_eq!(file.logging.verbose, false);
     assert_eq!(file.documentation.patterns.len(), 0);
     assert_eq!(file.input.files, &[Path::new("%LOCALAPPDATA%/Android/Sdk/platforms/android-28/android.jar")]);
+    assert_eq!(file.input.classpath.len(), 0);
     assert_eq!(file.output.path, Path::new("android28.rs"));
+    assert_eq!(file.includes.len(), 0);
     assert_eq!(file.ignores.len(), 0);
     assert_eq!(file.renames.len(), 0);
+    assert_eq!(file.externs.len(), 0);
+}
+
+#[test] fn ignore_and_rename_patterns() {
+    let toml = r#"
+        [input]
+        files = ["android.jar"]
+
+        [output]
+        path = "android28.rs"
+
+        [[ignore]]
+        class = "java/io/*OutputStream"
+
+        [[ignore]]
+        class = "java/io/DataOutput?"
+        method = "write"
+        signature = "(I)V"
+
+        [[ignore]]
+        class = "android/.*"
+        field = "[A-Z_]+"
+        regex = true
+
+        [[rename]]
+        class = "java/*/Data*"
+        method = "write"
+        signature = "(I)V"
+        to = "write_int_${2}"
+
+        [[rename]]
+        class = "android/(\\w+)/.*"
+        method = "set(.+)"
+        regex = true
+        to = "${1}_assign_$2"
+
+        [[ignore]]
+        class = "java/(io"
+        regex = true
+    "#;
+    assert!(File::read_str(toml).is_err()); // Unbalanced regex
+
+    let file = File::read_str(toml.rsplitn(2, "[[ignore]]").nth(1).unwrap()).unwrap();
+
+    let ignore = file.ignores[0].key_regex().unwrap();
+    assert!( ignore.is_match("java/io/OutputStream"));
+    assert!( ignore.is_match("java/io/FileOutputStream"));
+    assert!(!ignore.is_match("java/io/OutputStreamWriter"));
+
+    let ignore = file.ignores[1].key_regex().unwrap();
+    assert!( ignore.is_match("java/io/DataOutputX\x1fwrite\x1f(I)V"));
+    assert!(!ignore.is_match("java/io/DataOutput\x1fwrite\x1f(I)V"));
+    assert!(!ignore.is_match("java/io/DataOutputX\x1fwrite\x1f([B)V"));
+
+    let ignore = file.ignores[2].key_regex().unwrap();
+    assert!( ignore.is_match("android/view/View\x1fVISIBLE"));
+    assert!(!ignore.is_match("android/view/View\x1fmLeft"));
+
+    let rename = file.renames[0].key_regex().unwrap();
+    let captures = rename.captures("java/io/DataOutputStream\x1fwrite\x1f(I)V").unwrap();
+    let mut to = String::new();
+    captures.expand(&file.renames[0].to, &mut to);
+    assert_eq!(to, "write_int_OutputStream");
+
+    let rename = file.renames[1].key_regex().unwrap();
+    assert!(rename.captures("android/view/View\x1fgetTag\x1f()Ljava/lang/Object;").is_none());
+    let captures = rename.captures("android/view/View\x1fsetTag\x1f(Ljava/lang/Object;)V").unwrap();
+    let mut to = String::new();
+    captures.expand(&file.renames[1].to, &mut to);
+    assert_eq!(to, "view_assign_Tag");
 }
 
 /// A [File] + context (directory path continaing the [File]).
@@ -3,17 +3,31 @@
 #[allow(unused_imports)] use super::*;
 
 mod context;
+mod enums;
 mod fields;
+mod generics;
 mod known_docs_url;
 mod methods;
 mod modules;
+mod natives;
 mod preamble;
 mod structs;
 
 pub use context::Context;
+use context::KnownClass;
+use enums::*;
 use fields::*;
+use generics::*;
 use known_docs_url::*;
 use methods::*;
 use modules::*;
+use natives::*;
 use preamble::*;
 use structs::*;
+
+/// `s` as a rust string literal, NUL terminated for passing to JNI - e.g. `"java/lang/Object\0"`.
+fn emit_cstr(s: &str) -> String {
+    let mut s = format!("{:?}", s); // XXX
+    s.insert_str(s.len() - 1, "\\0");
+    s
+}
@@ -1,3 +1,4 @@
+use crate::class_file::*;
 use crate::emit_rust::*;
 
 use jreflection::class;
@@ -14,16 +15,39 @@ pub struct Context<'a> {
     pub(crate) features:    BTreeMap<String, BTreeSet<String>>,
     pub(crate) progress:    Mutex<util::Progress>,
     pub(crate) files:       &'a util::ConcurrentDedupeFileSet,
+    pub(crate) verify:      bool,
+    pub(crate) differences: Mutex<Vec<util::FileDifference>>,
+    pub(crate) known:       HashMap<String, KnownClass>,
+    pub(crate) extern_features: Mutex<BTreeMap<String, BTreeSet<String>>>,
+}
+
+/// A class read from `[input] files` or `[input] classpath`, kept around to resolve references to it.
+pub(crate) struct KnownClass {
+    /// Bindings are generated for this class (it was read from `[input] files`, and wasn't `[[ignore]]`d.)
+    pub(crate) bound:       bool,
+    pub(crate) super_path:  Option<class::IdBuf>,
+    pub(crate) interfaces:  Vec<class::IdBuf>,
+
+    /// Bindings aren't generated for this class because it's outside the \[[include\]]d classes and their dependencies.
+    pub(crate) excluded:    bool,
+
+    /// The class's generic signature, if it has one and we're generating `generics = "phantom"` bindings.
+    pub(crate) signature:   Option<ClassSignature>,
 }
 
 impl<'a> Context<'a> {
-    pub fn new(files: &'a util::ConcurrentDedupeFileSet, config: &'a config::runtime::Config) -> Self {
+    /// If `verify` is set, generated files are only compared against what's on disk (see `differences`), never written.
+    pub fn new(files: &'a util::ConcurrentDedupeFileSet, config: &'a config::runtime::Config, verify: bool) -> Self {
         Self {
             config,
             module: Default::default(),
             features: BTreeMap::new(),
             progress: Mutex::new(util::Progress::with_duration(Duration::from_millis(if config.logging_verbose { 0 } else { 300 }))),
             files,
+            verify,
+            differences: Mutex::new(Vec::new()),
+            known: HashMap::new(),
+            extern_features: Mutex::new(BTreeMap::new()),
         }
     }
 
@@ -33,33 +57,80 @@ impl<'a> Context<'a> {
         Ok(format!("{}::{}", m, s))
     }
 
-    pub fn add_struct(&mut self, class: jreflection::Class) -> Result<(), Box<dyn Error>> {
-        if self.config.ignore_classes.contains(class.path.as_str()) {
-            return Ok(())
+    /// The \[[extern\]] mapping `java_class` onto an existing crate, if any.
+    pub(crate) fn extern_for(&self, java_class: class::Id) -> Option<&config::toml::Extern> {
+        self.config.externs.iter().find(|ext| java_class.as_str().starts_with(ext.jni_prefix.as_str()))
+    }
+
+    /// The feature generated code referencing `java_class` should require, if any.
+    pub fn required_feature_for(&self, java_class: class::Id) -> Result<Option<String>, Box<dyn Error>> {
+        let feature = Struct::feature_for(self, java_class)?;
+        match self.extern_for(java_class) {
+            None => Ok(Some(feature)),
+            Some(config::toml::Extern { feature_crate: None, .. }) => Ok(None),
+            Some(config::toml::Extern { feature_crate: Some(feature_crate), .. }) => {
+                let mut extern_features = self.extern_features.lock().unwrap();
+                extern_features.entry(feature.clone()).or_insert(BTreeSet::new()).insert(format!("{}/{}", feature_crate, feature));
+                extern_features.entry("all".to_owned()).or_insert(BTreeSet::new()).insert(format!("{}/all", feature_crate));
+                Ok(Some(feature))
+            },
         }
+    }
 
-        let s = Struct::new(self, class)?;
-        let scope = if let Some(s) = s.rust.local_scope() { s } else { /* !local_scope = not part of this module, skip! */ return Ok(()); };
+    /// Why generated code can't refer to `java_class`, if it can't.
+    pub fn class_reject_reason(&self, java_class: class::Id) -> Option<&'static str> {
+        if self.config.ignore_class(java_class.as_str()) {
+            return Some("Type is [[ignore]]d");
+        }
+        if self.extern_for(java_class).is_some() {
+            return None;
+        }
+        match self.known.get(java_class.as_str()) {
+            Some(known) if known.bound  => None,
+            Some(known) if known.excluded => Some("Type is outside the [[include]]d classes and their dependencies"),
+            Some(_)                     => Some("Type is only on the [input] classpath, so has no bindings"),
+            None                        => Some("Type wasn't found in any [input] files or classpath"),
+        }
+    }
 
-        if self.config.codegen.feature_per_struct {
-            if let Ok(feature) = Struct::feature_for(self, s.java.path.as_id()) {
-                let mut subfeatures = Vec::new();
-                if let Some(parent) = s.java.super_path.as_ref() {
-                    if let Ok(sf) = Struct::feature_for(self, parent.as_id()) {
-                        subfeatures.push(sf);
-                    }
-                }
+    /// The nearest superclass of `class` that generated code can refer to, and the interfaces it can refer to -
+    /// including those of any superclasses or superinterfaces skipped along the way.
+    pub(crate) fn resolve_supertypes(&self, class: &jreflection::Class) -> (Option<class::IdBuf>, Vec<class::IdBuf>) {
+        let mut interfaces = Vec::new();
+        self.resolve_interfaces(&class.interfaces[..], &mut interfaces);
 
-                for interface in s.java.interfaces.iter() {
-                    if let Ok(subfeature) = Struct::feature_for(self, interface.as_id()) {
-                        subfeatures.push(subfeature);
-                    }
-                }
+        let mut super_path = class.super_path.as_ref();
+        while let Some(path) = super_path {
+            if self.class_reject_reason(path.as_id()).is_none() {
+                return (Some(path.clone()), interfaces);
+            }
+            let known = if let Some(known) = self.known.get(path.as_str()) { known } else { break };
+            self.resolve_interfaces(&known.interfaces[..], &mut interfaces);
+            super_path = known.super_path.as_ref();
+        }
+        (None, interfaces)
+    }
 
-                let global_subfeatures = self.features.entry(feature).or_insert(BTreeSet::new());
-                for sf in subfeatures { global_subfeatures.insert(sf); }
+    fn resolve_interfaces(&self, candidates: &[class::IdBuf], interfaces: &mut Vec<class::IdBuf>) {
+        for interface in candidates {
+            if interfaces.contains(interface) {
+                // Already resolved
+            } else if self.class_reject_reason(interface.as_id()).is_none() {
+                interfaces.push(interface.clone());
+            } else if let Some(known) = self.known.get(interface.as_str()) {
+                self.resolve_interfaces(&known.interfaces[..], interfaces);
             }
         }
+    }
+
+    pub fn add_struct(&mut self, class: jreflection::Class, attributes: ClassAttributes) -> Result<(), Box<dyn Error>> {
+        if self.config.ignore_class(class.path.as_str()) {
+            return Ok(())
+        }
+
+        let s = Struct::new(self, class, attributes)?;
+        let signature = self.generic_signature(&s.attributes);
+        let scope = if let Some(s) = s.rust.local_scope() { s } else { /* !local_scope = not part of this module, skip! */ return Ok(()); };
 
         let mut rust_mod = &mut self.module;
         for fragment in scope {
@@ -68,13 +139,187 @@ impl<'a> Context<'a> {
         if rust_mod.structs.contains_key(&s.rust.struct_name) {
             return io_data_err!("Unable to add_struct(): java class name {:?} was already added", &s.rust.struct_name)?
         }
+        self.known.insert(s.java.path.as_str().to_owned(), KnownClass {
+            bound:      true,
+            super_path: s.java.super_path.clone(),
+            interfaces: s.java.interfaces.clone(),
+            excluded:   false,
+            signature,
+        });
         rust_mod.structs.insert(s.rust.struct_name.clone(), s);
 
         Ok(())
     }
 
+    /// Remember `class` for resolving supertypes etc., without generating bindings for it.
+    pub fn add_classpath_class(&mut self, class: jreflection::Class, attributes: ClassAttributes) {
+        let signature = self.generic_signature(&attributes);
+        self.known.entry(class.path.as_str().to_owned()).or_insert(KnownClass {
+            bound:      false,
+            super_path: class.super_path,
+            interfaces: class.interfaces,
+            excluded:   false,
+            signature,
+        });
+    }
+
+    fn generic_signature(&self, attributes: &ClassAttributes) -> Option<ClassSignature> {
+        if self.config.codegen.generics != config::toml::GenericsStyle::Phantom { return None; }
+        ClassSignature::parse(attributes.signature.as_ref()?.as_str())
+    }
+
+    /// Drop structs outside the transitive closure of the \[[include\]]d classes, if there are any.  Done after all
+    /// inputs are read, as referenced types may be read after the classes referencing them.
+    pub fn resolve_includes(&mut self) {
+        if self.config.includes.is_empty() { return; }
+
+        let mut structs = HashMap::new();
+        let mut pending = Vec::new();
+        self.module.visit_structs(&mut |s| {
+            let path = s.java.path.as_str();
+            structs.insert(path, s);
+            if let Some(depth) = self.config.include_depth(path) {
+                pending.push((path.to_owned(), depth));
+            }
+        });
+
+        // How many more levels of referenced types to follow from each reached class
+        let mut reached = HashMap::<String, usize>::new();
+        while let Some((path, depth)) = pending.pop() {
+            if reached.get(&path).is_some_and(|&prev| prev >= depth) { continue; }
+            reached.insert(path.clone(), depth);
+
+            if let Some(known) = self.known.get(&path) {
+                // Supertypes don't count against depth, so the inheritance chain doesn't change with it.
+                for supertype in known.super_path.iter().chain(known.interfaces.iter()) {
+                    pending.push((supertype.as_str().to_owned(), depth));
+                }
+            }
+
+            if depth == 0 { continue; }
+            if let Some(s) = structs.get(path.as_str()) {
+                let native_traits = self.config.codegen.native_traits;
+                for method in s.java.methods.iter().filter(|m| m.is_public() || (native_traits && m.is_native())) {
+                    let descriptor = method.descriptor();
+                    for ty in descriptor.arguments().chain(std::iter::once(descriptor.return_type())) {
+                        if let Some(class) = referenced_class(&ty) { pending.push((class.to_owned(), depth - 1)); }
+                    }
+                }
+                for field in s.java.fields.iter().filter(|f| f.is_public()) {
+                    if let Some(class) = referenced_class(&field.descriptor()) { pending.push((class.to_owned(), depth - 1)); }
+                }
+            }
+        }
+
+        for (path, known) in self.known.iter_mut() {
+            if known.bound && !reached.contains_key(path) {
+                known.bound     = false;
+                known.excluded  = true;
+            }
+        }
+        self.module.retain_structs(&mut |s| reached.contains_key(s.java.path.as_str()));
+    }
+
+    /// Record which features each struct depends on.  Done after all inputs are read, as supertypes may be read after
+    /// their subtypes, and only supertypes with bindings are depended upon.
+    pub fn resolve_features(&mut self) {
+        if !self.config.codegen.feature_per_struct { return; }
+
+        let mut features = BTreeMap::new();
+        self.module.visit_structs(&mut |s| {
+            if let Ok(feature) = Struct::feature_for(self, s.java.path.as_id()) {
+                let subfeatures = features.entry(feature.clone()).or_insert(BTreeSet::new());
+                s.rust_supertypes(self, subfeatures);
+                subfeatures.remove(&feature); // e.g. String implements Comparable<String> - cargo rejects features depending on themselves
+            }
+        });
+        self.features = features;
+    }
+
     pub fn write(&self, out: &mut impl io::Write) -> io::Result<()> {
-        write_preamble(out)?;
+        write_preamble(out, self.config.codegen.native_traits)?;
         self.module.write(self, "", out)
     }
 }
+
+/// The class `ty` refers to, if any - including the element class of arrays.
+fn referenced_class<'d>(ty: &jreflection::field::Descriptor<'d>) -> Option<&'d str> {
+    match ty {
+        jreflection::field::Descriptor::Single(jreflection::field::BasicType::Class(class))                  => Some(class.as_str()),
+        jreflection::field::Descriptor::Array { inner: jreflection::field::BasicType::Class(class), .. }     => Some(class.as_str()),
+        _ => None,
+    }
+}
+
+#[test] fn classpath_supertypes_skipped_to_nearest_bound_ancestor() {
+    use crate::util::test_class_file::*;
+
+    let dir = temp_dir("classpath-supertypes");
+    let (classes, classpath) = (dir.join("classes"), dir.join("classpath"));
+    write_class(&classes,   "p/Base",    &class_file(ACC_PUBLIC | ACC_SUPER, "p/Base",    Some("java/lang/Object"), None, &[])).unwrap();
+    write_class(&classpath, "cp/Upper",  &class_file(ACC_PUBLIC | ACC_SUPER, "cp/Upper",  Some("p/Base"),           None, &[])).unwrap();
+    write_class(&classpath, "cp/Middle", &class_file(ACC_PUBLIC | ACC_SUPER, "cp/Middle", Some("cp/Upper"),         None, &[])).unwrap();
+    write_class(&classes,   "p/Child",   &class_file(ACC_PUBLIC | ACC_SUPER, "p/Child",   Some("cp/Middle"),        None, &[])).unwrap();
+
+    let generated = generate(&dir, r#"
+        [input]
+        files = ["classes"]
+        classpath = ["classpath"]
+
+        [output]
+        path = "src/generated.rs"
+    "#);
+    let child = &generated[generated.find("class Child (").unwrap()..];
+    let child = &child[..child.find('{').unwrap()];
+    assert!(child.contains("extends crate::p::Base"), "{}", child);
+    assert!(!generated.contains("cp::"), "{}", generated);
+    assert!(!generated.contains("class Middle ("), "{}", generated);
+}
+
+#[test] fn include_depth_closure() {
+    use crate::util::test_class_file::*;
+
+    let generate_with_depth = |depth: usize| {
+        let dir = temp_dir(&format!("include-depth-{}", depth));
+        let classes = dir.join("classes");
+        let class = |class: &str, super_class: &str, fields: &[TestField]| {
+            write_class(&classes, class, &class_file(ACC_PUBLIC | ACC_SUPER, class, Some(super_class), None, fields)).unwrap();
+        };
+        class("p/Grand",    "java/lang/Object", &[]);
+        class("p/Parent",   "p/Grand",          &[(ACC_PUBLIC, "parent_field", "Lq/C;", None)]);
+        class("p/Root",     "p/Parent",         &[(ACC_PUBLIC, "a", "Lq/A;", None)]);
+        class("q/A",        "java/lang/Object", &[(ACC_PUBLIC, "b", "[Lq/B;", None)]);
+        class("q/B",        "java/lang/Object", &[]);
+        class("q/C",        "java/lang/Object", &[]);
+        class("q/Unreferenced", "java/lang/Object", &[]);
+
+        generate(&dir, &format!(r#"
+            [input]
+            files = ["classes"]
+
+            [output]
+            path = "src/generated.rs"
+
+            [[include]]
+            class = "p/Root"
+            depth = {}
+        "#, depth))
+    };
+
+    // Supertypes don't count against depth - but their member types do.
+    let depth0 = generate_with_depth(0);
+    for class in &["class Root (", "class Parent (", "class Grand (", "class Object ("] {
+        assert!(depth0.contains(class), "{:?} not generated:\n{}", class, depth0);
+    }
+    for class in &["class A (", "class B (", "class C (", "class Unreferenced ("] {
+        assert!(!depth0.contains(class), "{:?} generated:\n{}", class, depth0);
+    }
+
+    let depth1 = generate_with_depth(1);
+    for class in &["class Root (", "class Parent (", "class Grand (", "class A (", "class C ("] {
+        assert!(depth1.contains(class), "{:?} not generated:\n{}", class, depth1);
+    }
+    for class in &["class B (", "class Unreferenced ("] {
+        assert!(!depth1.contains(class), "{:?} generated:\n{}", class, depth1);
+    }
+}
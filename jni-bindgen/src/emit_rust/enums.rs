@@ -0,0 +1,214 @@
+use crate::emit_rust::*;
+use crate::identifiers::*;
+
+use std::io;
+
+
+
+/// The constants of a Java `enum` class, emitted as a plain `#[repr(i32)]` Rust enum (in ordinal order) that can be
+/// `match`ed on, plus conversions to and from the Java objects.
+pub struct EnumMirror<'a> {
+    class:      &'a Struct,
+    constants:  Result<Vec<EnumConstant<'a>>, String>,
+}
+
+struct EnumConstant<'a> {
+    java:       &'a jreflection::Field,
+    variant:    &'a str,
+}
+
+impl<'a> EnumMirror<'a> {
+    pub fn new(class: &'a Struct) -> Self {
+        // javac emits enum constants' fields in declaration - and thus ordinal - order.
+        let constants = class.java.fields.iter().filter(|f| f.is_enum() && f.is_static()).map(|java| {
+            match RustIdentifier::from_str(java.name.as_str()) {
+                RustIdentifier::Identifier(variant)                 => Ok(EnumConstant { java, variant }),
+                RustIdentifier::KeywordRawSafe(variant)             => Ok(EnumConstant { java, variant }),
+                RustIdentifier::KeywordUnderscorePostfix(variant)   => Ok(EnumConstant { java, variant }),
+                RustIdentifier::NonIdentifier(name)                 => Err(format!("Enum constant {:?} has no rust equivalent (yet?)", name)),
+            }
+        }).collect();
+
+        Self { class, constants }
+    }
+
+    pub fn enum_name(&self) -> String { format!("{}Enum", self.class.rust.struct_name) }
+
+    pub fn emit(&self, context: &Context, indent: &str, out: &mut impl io::Write) -> io::Result<()> {
+        if !self.class.java.is_enum() || !self.class.java.is_public() { return Ok(()); }
+
+        let enum_name   = self.enum_name();
+        let struct_name = &self.class.rust.struct_name;
+        let cfg = match Struct::feature_for(context, self.class.java.path.as_id()) {
+            Ok(feature) => format!("{}#[cfg(any(feature = \"all\", feature = {:?}))]", indent, feature),
+            Err(_)      => String::new(),
+        };
+
+        let constants = match self.constants.as_ref() {
+            Ok(constants) if constants.is_empty() => return Ok(()), // #[repr(i32)] requires variants
+            Ok(constants) => constants,
+            Err(reason) => {
+                writeln!(out, "")?;
+                writeln!(out, "{}// Not emitting {}: {}", indent, &enum_name, reason)?;
+                return Ok(());
+            },
+        };
+
+        // The enum shares the struct's module, where e.g. a Java class com.example.ColorEnum would map to the same name.
+        if let Some(module) = self.class.rust.local_scope().and_then(|scope| context.module.find(scope)) {
+            let collision = if let Some(s) = module.structs.get(&enum_name) {
+                Some(format!("collides with the struct for {}", s.java.path.as_str()))
+            } else if module.modules.contains_key(&enum_name) {
+                Some(format!("collides with mod {}", &enum_name))
+            } else {
+                None
+            };
+            if let Some(reason) = collision {
+                writeln!(out, "")?;
+                writeln!(out, "{}// Not emitting {}: {}", indent, &enum_name, reason)?;
+                return Ok(());
+            }
+        }
+
+        // static_env = "implicit":  from_rust takes no env parameter, and returns a Global instead of a Local.
+        let implicit_env = context.config.codegen.static_env == config::toml::StaticEnvStyle::Implicit;
+
+        writeln!(out, "")?;
+        writeln!(out, "{}/// The constants of [{}](struct.{}.html), in ordinal order.", indent, struct_name, struct_name)?;
+        writeln!(out, "{}///", indent)?;
+        writeln!(out, "{}/// Ordinals are those of the classes these bindings were generated from.", indent)?;
+        if !cfg.is_empty() { writeln!(out, "{}", &cfg)?; }
+        writeln!(out, "{}#[allow(non_camel_case_types)]", indent)?;
+        writeln!(out, "{}#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]", indent)?;
+        writeln!(out, "{}#[repr(i32)]", indent)?;
+        writeln!(out, "{}pub enum {} {{", indent, &enum_name)?;
+        for (ordinal, constant) in constants.iter().enumerate() {
+            if let Some(url) = KnownDocsUrl::from_field(context, self.class.java.path.as_str(), constant.java.name.as_str(), constant.java.descriptor()) {
+                writeln!(out, "{}    /// {}", indent, url)?;
+            } else {
+                writeln!(out, "{}    /// {}", indent, constant.java.name.as_str())?;
+            }
+            writeln!(out, "{}    {} = {},", indent, constant.variant, ordinal)?;
+        }
+        writeln!(out, "{}}}", indent)?;
+
+        writeln!(out, "")?;
+        if !cfg.is_empty() { writeln!(out, "{}", &cfg)?; }
+        writeln!(out, "{}impl {} {{", indent, &enum_name)?;
+        writeln!(out, "{}    /// Every constant, in ordinal order.", indent)?;
+        write!(out, "{}    pub const VALUES : &'static [{}] = &[", indent, &enum_name)?;
+        for (idx, constant) in constants.iter().enumerate() {
+            if idx != 0 { write!(out, ", ")?; }
+            write!(out, "{}::{}", &enum_name, constant.variant)?;
+        }
+        writeln!(out, "];")?;
+        writeln!(out, "")?;
+        writeln!(out, "{}    /// Iterate over every constant, in ordinal order.", indent)?;
+        writeln!(out, "{}    pub fn values() -> impl __jni_bindgen::std::iter::Iterator<Item = Self> {{ Self::VALUES.iter().copied() }}", indent)?;
+        writeln!(out, "")?;
+        writeln!(out, "{}    /// The constant with the given Java `ordinal()`, if any.", indent)?;
+        writeln!(out, "{}    pub fn from_ordinal(ordinal: i32) -> __jni_bindgen::std::option::Option<Self> {{ Self::VALUES.get(ordinal as usize).copied() }}", indent)?;
+        writeln!(out, "")?;
+        writeln!(out, "{}    /// The Java `ordinal()` of this constant.", indent)?;
+        writeln!(out, "{}    pub fn ordinal(self) -> i32 {{ self as i32 }}", indent)?;
+        writeln!(out, "")?;
+        writeln!(out, "{}    /// The Java `name()` of this constant.", indent)?;
+        writeln!(out, "{}    pub fn name(self) -> &'static str {{", indent)?;
+        writeln!(out, "{}        match self {{", indent)?;
+        for constant in constants {
+            writeln!(out, "{}            {}::{} => {:?},", indent, &enum_name, constant.variant, constant.java.name.as_str())?;
+        }
+        writeln!(out, "{}        }}", indent)?;
+        writeln!(out, "{}    }}", indent)?;
+        writeln!(out, "{}}}", indent)?;
+
+        let (env_param, from_rust_ret) = if implicit_env {
+            (String::new(), "__jni_bindgen::Global<Self>")
+        } else {
+            ("env: &'env __jni_bindgen::Env, ".to_owned(), "__jni_bindgen::Local<'env, Self>")
+        };
+        let body_indent = if implicit_env { format!("{}    ", indent) } else { indent.to_owned() };
+
+        writeln!(out, "")?;
+        if !cfg.is_empty() { writeln!(out, "{}", &cfg)?; }
+        writeln!(out, "{}impl {} {{", indent, struct_name)?;
+        writeln!(out, "{}    /// The [{}](enum.{}.html) constant this object is, or None if it's unknown to these bindings (e.g. a", indent, &enum_name, &enum_name)?;
+        writeln!(out, "{}    /// constant added after they were generated.)  Constants are compared against their static fields, so this", indent)?;
+        writeln!(out, "{}    /// holds even if constants were inserted or reordered since.", indent)?;
+        writeln!(out, "{}    pub fn to_rust(&self) -> __jni_bindgen::std::option::Option<{}> {{", indent, &enum_name)?;
+        writeln!(out, "{}        unsafe {{", indent)?;
+        writeln!(out, "{}            let __jni_env = __jni_bindgen::Env::from_ptr(self.0.env);", indent)?;
+        writeln!(out, "{}            let is = |value: {}| {{", indent, &enum_name)?;
+        writeln!(out, "{}                let (__jni_class, __jni_field) = Self::__jni_constant_field(__jni_env, value);", indent)?;
+        writeln!(out, "{}                match __jni_env.get_static_object_field::<Self>(__jni_class, __jni_field) {{", indent)?;
+        writeln!(out, "{}                    __jni_bindgen::std::option::Option::Some(constant) => __jni_env.is_same_object(self.0.object, constant.0.object),", indent)?;
+        writeln!(out, "{}                    __jni_bindgen::std::option::Option::None => false,", indent)?;
+        writeln!(out, "{}                }}", indent)?;
+        writeln!(out, "{}            }};", indent)?;
+        writeln!(out, "{}            // Our ordinal() is the likely match, unless constants were inserted or reordered since we were generated.", indent)?;
+        writeln!(out, "{}            static __JNI_METHOD : __jni_bindgen::IdCache = __jni_bindgen::IdCache::new();", indent)?;
+        writeln!(out, "{}            let (_, __jni_method) = __JNI_METHOD.require_class_method(__jni_env, \"java/lang/Enum\\0\", \"ordinal\\0\", \"()I\\0\");", indent)?;
+        writeln!(out, "{}            let ordinal = __jni_env.call_int_method_a::<{}>(self.0.object, __jni_method, [].as_ptr()).ok()?;", indent, context.config.codegen.throwable_type.as_str())?;
+        writeln!(out, "{}            match {}::from_ordinal(ordinal) {{", indent, &enum_name)?;
+        writeln!(out, "{}                __jni_bindgen::std::option::Option::Some(value) if is(value) => __jni_bindgen::std::option::Option::Some(value),", indent)?;
+        writeln!(out, "{}                _ => {}::values().find(|&value| is(value)),", indent, &enum_name)?;
+        writeln!(out, "{}            }}", indent)?;
+        writeln!(out, "{}        }}", indent)?;
+        writeln!(out, "{}    }}", indent)?;
+        writeln!(out, "")?;
+        writeln!(out, "{}    /// The Java object for `value`, read from its static field.", indent)?;
+        writeln!(out, "{}    pub fn from_rust<'env>({}value: {}) -> __jni_bindgen::std::option::Option<{}> {{", indent, &env_param, &enum_name, from_rust_ret)?;
+        writeln!(out, "{}        unsafe {{", indent)?;
+        if implicit_env {
+            writeln!(out, "{}            __jni_bindgen::VM::with_implicit_env(|env| {{", indent)?;
+        }
+        writeln!(out, "{}            let (__jni_class, __jni_field) = Self::__jni_constant_field(env, value);", body_indent)?;
+        if implicit_env {
+            writeln!(out, "{}            __jni_bindgen::Unlocal::unlocal(env.get_static_object_field(__jni_class, __jni_field))", body_indent)?;
+            writeln!(out, "{}            }})", indent)?;
+        } else {
+            writeln!(out, "{}            env.get_static_object_field(__jni_class, __jni_field)", indent)?;
+        }
+        writeln!(out, "{}        }}", indent)?;
+        writeln!(out, "{}    }}", indent)?;
+        writeln!(out, "")?;
+        writeln!(out, "{}    unsafe fn __jni_constant_field(env: &__jni_bindgen::Env, value: {}) -> (__jni_bindgen::jni_sys::jclass, __jni_bindgen::jni_sys::jfieldID) {{", indent, &enum_name)?;
+        writeln!(out, "{}        match value {{", indent)?;
+        for constant in constants {
+            writeln!(out, "{}            {}::{} => {{", indent, &enum_name, constant.variant)?;
+            writeln!(out, "{}                static __JNI_FIELD : __jni_bindgen::IdCache = __jni_bindgen::IdCache::new();", indent)?;
+            writeln!(out, "{}                __JNI_FIELD.require_class_static_field(env, {}, {}, {})", indent, emit_cstr(self.class.java.path.as_str()), emit_cstr(constant.java.name.as_str()), emit_cstr(constant.java.descriptor_str()))?;
+            writeln!(out, "{}            }},", indent)?;
+        }
+        writeln!(out, "{}        }}", indent)?;
+        writeln!(out, "{}    }}", indent)?;
+        writeln!(out, "{}}}", indent)?;
+        Ok(())
+    }
+}
+
+#[test] fn enum_name_collision() {
+    use crate::util::test_class_file::*;
+
+    let dir = temp_dir("enum-name-collision");
+    let classes = dir.join("classes");
+    let enum_class = ACC_PUBLIC | ACC_SUPER | ACC_FINAL | ACC_ENUM;
+    let constant = ACC_PUBLIC | ACC_STATIC | ACC_FINAL | ACC_ENUM;
+    write_class(&classes, "java/lang/Enum", &class_file(ACC_PUBLIC | ACC_SUPER | ACC_ABSTRACT, "java/lang/Enum", Some("java/lang/Object"), None, &[])).unwrap();
+    write_class(&classes, "p/Color",        &class_file(enum_class, "p/Color", Some("java/lang/Enum"), None, &[(constant, "RED",    "Lp/Color;", None)])).unwrap();
+    write_class(&classes, "p/Shape",        &class_file(enum_class, "p/Shape", Some("java/lang/Enum"), None, &[(constant, "SQUARE", "Lp/Shape;", None)])).unwrap();
+    write_class(&classes, "p/ColorEnum",    &class_file(ACC_PUBLIC | ACC_SUPER, "p/ColorEnum", Some("java/lang/Object"), None, &[])).unwrap();
+
+    let generated = generate(&dir, r#"
+        [input]
+        files = ["classes"]
+
+        [output]
+        path = "src/generated.rs"
+    "#);
+    assert!(generated.contains("// Not emitting ColorEnum: collides with the struct for p/ColorEnum"), "{}", generated);
+    assert!(!generated.contains("pub enum ColorEnum"), "{}", generated);
+    assert!(!generated.contains("Option<ColorEnum>"), "{}", generated);
+    assert!(generated.contains("pub enum ShapeEnum {"), "{}", generated);
+    assert!(generated.contains("pub fn to_rust(&self) -> __jni_bindgen::std::option::Option<ShapeEnum> {"), "{}", generated);
+}
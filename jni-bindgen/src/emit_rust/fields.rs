@@ -1,28 +1,36 @@
+use crate::class_file::*;
 use crate::emit_rust::*;
 use crate::identifiers::*;
 
 use jreflection::class;
 use jreflection::field;
 
+use std::collections::BTreeSet;
 use std::io;
 
 pub struct Field<'a> {
     pub class:      &'a jreflection::Class,
     pub java:       &'a jreflection::Field,
+    signature:      Option<TypeSignature>,
     pub rust_names: Result<FieldMangling<'a>, IdentifierManglingError>,
     pub ignored:    bool,
 }
 
 impl<'a> Field<'a> {
-    pub fn new(context: &Context, class: &'a jreflection::Class, java: &'a jreflection::Field) -> Self {
-        let java_class_field = format!("{}\x1f{}", class.path.as_str(), &java.name);
-        let ignored = context.config.ignore_class_fields.contains(&java_class_field);
-        let renamed_to = context.config.rename_class_fields.get(&java_class_field).map(|s| s.as_str());
+    pub fn new(context: &Context, class: &'a jreflection::Class, attributes: &ClassAttributes, java: &'a jreflection::Field) -> Self {
+        let ignored = context.config.ignore_class_field(class.path.as_str(), &java.name);
+        let renamed_to = context.config.rename_class_field(class.path.as_str(), &java.name);
+        let signature = if context.config.codegen.generics == config::toml::GenericsStyle::Phantom {
+            attributes.field(java.name.as_str(), java.descriptor_str()).and_then(|f| f.signature.as_ref()).and_then(|s| TypeSignature::parse(s.as_str()))
+        } else {
+            None
+        };
 
         let result = Self {
             class,
             java,
-            rust_names: context.config.codegen.field_naming_style.mangle(java, renamed_to),
+            signature,
+            rust_names: context.config.codegen.field_naming_style.mangle(java, renamed_to.as_deref()),
             ignored,
         };
         result
@@ -34,12 +42,26 @@ impl<'a> Field<'a> {
         if !self.java.is_public()   { emit_reject_reasons.push("Non-public field"); }
         if self.ignored             { emit_reject_reasons.push("[[ignore]]d"); }
 
-        let mut required_feature = None;
+        let mut required_features = BTreeSet::new();
+
+        // static_env = "implicit":  No env parameter, so getters can't borrow one - return Globals instead of Locals.
+        let implicit_env = self.java.is_static() && context.config.codegen.static_env == config::toml::StaticEnvStyle::Implicit;
+        let local = if implicit_env { "__jni_bindgen::Global<" } else { "__jni_bindgen::Local<'env, " };
+
+        // generics = "phantom":  e.g. `List<E>.someField` of type `E`
+        let type_scope = if self.java.is_static() { TypeScope::default() } else { TypeScope::for_class(context, self.class.path.as_id()) };
+        let generic_type = if self.java.is_constant() { None } else {
+            self.signature.as_ref().and_then(|ty| rust_type(context, &type_scope, ty, &mut required_features, &mut BTreeSet::new()))
+        };
 
         let descriptor = self.java.descriptor();
         let rust_set_type_buffer;
         let rust_get_type_buffer;
-        let (rust_set_type, rust_get_type) = match descriptor {
+        let (rust_set_type, rust_get_type) = if let Some(generic_type) = generic_type.as_ref() {
+            rust_set_type_buffer = format!("impl __jni_bindgen::std::convert::Into<__jni_bindgen::std::option::Option<&'obj {}>>", generic_type);
+            rust_get_type_buffer = format!("__jni_bindgen::std::option::Option<{}{}>>", local, generic_type);
+            (rust_set_type_buffer.as_str(), rust_get_type_buffer.as_str())
+        } else { match descriptor {
             field::Descriptor::Single(field::BasicType::Boolean) => ("bool", "bool"),
             field::Descriptor::Single(field::BasicType::Byte)    => ("i8", "i8"),
             field::Descriptor::Single(field::BasicType::Char)    => ("__jni_bindgen::jchar", "__jni_bindgen::jchar"),
@@ -54,15 +76,16 @@ impl<'a> Field<'a> {
                 ("()", "()")
             },
             field::Descriptor::Single(field::BasicType::Class(class)) => {
-                if let Ok(feature) = Struct::feature_for(context, class) {
-                    required_feature = Some(feature);
+                if let Some(reason) = context.class_reject_reason(class) { emit_reject_reasons.push(reason); }
+                if let Ok(feature) = context.required_feature_for(class) {
+                    required_features.extend(feature);
                 } else {
                     emit_reject_reasons.push("ERROR:  Unable to resolve class feature");
                 }
 
                 if let Ok(fqn) = Struct::fqn_for(context, class) {
                     rust_set_type_buffer = format!("impl __jni_bindgen::std::convert::Into<__jni_bindgen::std::option::Option<&'obj {}>>", &fqn);
-                    rust_get_type_buffer = format!("__jni_bindgen::std::option::Option<__jni_bindgen::Local<'env, {}>>", &fqn);
+                    rust_get_type_buffer = format!("__jni_bindgen::std::option::Option<{}{}>>", local, &fqn);
                     (rust_set_type_buffer.as_str(), rust_get_type_buffer.as_str())
                 } else {
                     emit_reject_reasons.push("ERROR:  Unable to resolve class FQN");
@@ -84,8 +107,9 @@ impl<'a> Field<'a> {
                     field::BasicType::Float        => buffer.push_str("__jni_bindgen::FloatArray"),
                     field::BasicType::Double       => buffer.push_str("__jni_bindgen::DoubleArray"),
                     field::BasicType::Class(class) => {
-                        if let Ok(feature) = Struct::feature_for(context, class) {
-                            required_feature = Some(feature);
+                        if let Some(reason) = context.class_reject_reason(class) { emit_reject_reasons.push(reason); }
+                        if let Ok(feature) = context.required_feature_for(class) {
+                            required_features.extend(feature);
                         } else {
                             emit_reject_reasons.push("ERROR:  Unable to resolve class feature");
                         }
@@ -113,10 +137,10 @@ impl<'a> Field<'a> {
                 }
 
                 rust_set_type_buffer = format!("impl __jni_bindgen::std::convert::Into<__jni_bindgen::std::option::Option<&'obj {}>>", &buffer);
-                rust_get_type_buffer = format!("__jni_bindgen::std::option::Option<__jni_bindgen::Local<'env, {}>>", &buffer);
+                rust_get_type_buffer = format!("__jni_bindgen::std::option::Option<{}{}>>", local, &buffer);
                 (rust_set_type_buffer.as_str(), rust_get_type_buffer.as_str())
             },
-        };
+        }};
 
         let field_fragment = match self.java.descriptor() { // Contents of {get,set}_[static_]..._field
             field::Descriptor::Single(field::BasicType::Void)        => "void",
@@ -166,7 +190,8 @@ impl<'a> Field<'a> {
             writeln!(out, "{}// Not emitting: {}", indent, reason)?;
         }
 
-        let env_param = if self.java.is_static() { "env: &'env __jni_bindgen::Env" } else { "&'env self" };
+        let env_param = if implicit_env { "" } else if self.java.is_static() { "env: &'env __jni_bindgen::Env" } else { "&'env self" };
+        let body_indent = if implicit_env { format!("{}    ", indent) } else { indent.clone() };
 
         let url = KnownDocsUrl::from_field(context, self.class.path.as_str(), self.java.name.as_str(), self.java.descriptor());
         let url = url.as_ref();
@@ -177,11 +202,7 @@ impl<'a> Field<'a> {
                 if let Some(url) = url {
                     writeln!(out, "{}/// {} {}", indent, &keywords, url)?;
                 }
-                if let Some(required_feature) = required_feature.as_ref() {
-                    writeln!(out, "{}///", indent)?;
-                    writeln!(out, "{}/// Required feature: {:?}", indent, required_feature)?;
-                    writeln!(out, "{}#[cfg(any(feature = \"all\", feature = {:?}))]", indent, required_feature)?;
-                }
+                emit_required_features(indent.as_str(), &required_features, out)?;
                 match descriptor {
                     field::Descriptor::Single(field::BasicType::Char)       => writeln!(out, "{}{}pub const {} : {} = {}({});", indent, &attributes, constant, rust_get_type, rust_get_type, value)?,
                     field::Descriptor::Single(field::BasicType::Boolean)    => writeln!(out, "{}{}pub const {} : {} = {};", indent, &attributes, constant, rust_get_type, if value == &field::Constant::Integer(0) { "false" } else { "true" })?,
@@ -195,18 +216,20 @@ impl<'a> Field<'a> {
                 } else {
                     writeln!(out, "{}/// **get** {} {}", indent, &keywords, self.java.name.as_str())?;
                 }
-                if let Some(required_feature) = required_feature.as_ref() {
-                    writeln!(out, "{}///", indent)?;
-                    writeln!(out, "{}/// Required feature: {:?}", indent, required_feature)?;
-                    writeln!(out, "{}#[cfg(any(feature = \"all\", feature = {:?}))]", indent, required_feature)?;
-                }
+                emit_required_features(indent.as_str(), &required_features, out)?;
                 writeln!(out, "{}{}pub fn {}<'env>({}) -> {} {{", indent, &attributes, get, env_param, rust_get_type)?;
                 writeln!(out, "{}    unsafe {{", indent)?;
-                if !self.java.is_static() {
+                if implicit_env {
+                    writeln!(out, "{}        __jni_bindgen::VM::with_implicit_env(|env| {{", indent)?;
+                } else if !self.java.is_static() {
                     writeln!(out, "{}        let env = __jni_bindgen::Env::from_ptr(self.0.env);", indent)?;
                 }
-                writeln!(out, "{}        let (__jni_class, __jni_field) = env.require_class_{}field({}, {}, {});", indent, if self.java.is_static() { "static_" } else { "" }, emit_cstr(self.class.path.as_str()), emit_cstr(self.java.name.as_str()), emit_cstr(self.java.descriptor_str()) )?;
-                if self.java.is_static() {
+                writeln!(out, "{}        static __JNI_FIELD : __jni_bindgen::IdCache = __jni_bindgen::IdCache::new();", body_indent)?;
+                writeln!(out, "{}        let (__jni_class, __jni_field) = __JNI_FIELD.require_class_{}field(env, {}, {}, {});", body_indent, if self.java.is_static() { "static_" } else { "" }, emit_cstr(self.class.path.as_str()), emit_cstr(self.java.name.as_str()), emit_cstr(self.java.descriptor_str()) )?;
+                if implicit_env {
+                    writeln!(out, "{}        __jni_bindgen::Unlocal::unlocal(env.get_static_{}_field(__jni_class, __jni_field))", body_indent, field_fragment)?;
+                    writeln!(out, "{}        }})", indent)?;
+                } else if self.java.is_static() {
                     writeln!(out, "{}        env.get_static_{}_field(__jni_class, __jni_field)", indent, field_fragment)?;
                 } else {
                     writeln!(out, "{}        env.get_{}_field(self.0.object, __jni_field)", indent, field_fragment)?;
@@ -217,6 +240,14 @@ impl<'a> Field<'a> {
                 // Setter
                 if !self.java.is_final() {
                     let lifetimes = if field_fragment == "object" { "'env, 'obj" } else { "'env" };
+                    let mut where_clause = String::new(); // `&'obj T` requires `T: 'obj`, which `&'env self` doesn't imply
+                    if generic_type.is_some() && !self.java.is_static() {
+                        for (idx, param) in struct_type_params(context, self.class.path.as_id()).iter().enumerate() {
+                            where_clause.push_str(if idx == 0 { " where " } else { ", " });
+                            where_clause.push_str(param);
+                            where_clause.push_str(": 'obj");
+                        }
+                    }
 
                     writeln!(out, "")?;
                     if let Some(url) = url {
@@ -224,18 +255,21 @@ impl<'a> Field<'a> {
                     } else {
                         writeln!(out, "{}/// **set** {} {}", indent, &keywords, self.java.name.as_str())?;
                     }
-                    if let Some(required_feature) = required_feature.as_ref() {
-                        writeln!(out, "{}///", indent)?;
-                        writeln!(out, "{}/// Required feature: {:?}", indent, required_feature)?;
-                        writeln!(out, "{}#[cfg(any(feature = \"all\", feature = {:?}))]", indent, required_feature)?;
-                    }
-                    writeln!(out, "{}{}pub fn {}<{}>({}, value: {}) {{", indent, &attributes, set, lifetimes, env_param, rust_set_type)?;
+                    emit_required_features(indent.as_str(), &required_features, out)?;
+                    let params = if implicit_env { format!("value: {}", rust_set_type) } else { format!("{}, value: {}", env_param, rust_set_type) };
+                    writeln!(out, "{}{}pub fn {}<{}>({}){} {{", indent, &attributes, set, lifetimes, params, where_clause)?;
                     writeln!(out, "{}    unsafe {{", indent)?;
-                    if !self.java.is_static() {
+                    if implicit_env {
+                        writeln!(out, "{}        __jni_bindgen::VM::with_implicit_env(|env| {{", indent)?;
+                    } else if !self.java.is_static() {
                         writeln!(out, "{}        let env = __jni_bindgen::Env::from_ptr(self.0.env);", indent)?;
                     }
-                    writeln!(out, "{}        let (__jni_class, __jni_field) = env.require_class_{}field({}, {}, {});", indent, if self.java.is_static() { "static_" } else { "" }, emit_cstr(self.class.path.as_str()), emit_cstr(self.java.name.as_str()), emit_cstr(self.java.descriptor_str()) )?;
-                    if self.java.is_static() {
+                    writeln!(out, "{}        static __JNI_FIELD : __jni_bindgen::IdCache = __jni_bindgen::IdCache::new();", body_indent)?;
+                    writeln!(out, "{}        let (__jni_class, __jni_field) = __JNI_FIELD.require_class_{}field(env, {}, {}, {});", body_indent, if self.java.is_static() { "static_" } else { "" }, emit_cstr(self.class.path.as_str()), emit_cstr(self.java.name.as_str()), emit_cstr(self.java.descriptor_str()) )?;
+                    if implicit_env {
+                        writeln!(out, "{}        env.set_static_{}_field(__jni_class, __jni_field, value)", body_indent, field_fragment)?;
+                        writeln!(out, "{}        }})", indent)?;
+                    } else if self.java.is_static() {
                         writeln!(out, "{}        env.set_static_{}_field(__jni_class, __jni_field, value)", indent, field_fragment)?;
                     } else {
                         writeln!(out, "{}        env.set_{}_field(self.0.object, __jni_field, value)", indent, field_fragment)?;
@@ -256,8 +290,22 @@ impl<'a> Field<'a> {
     }
 }
 
-fn emit_cstr(s: &str) -> String {
-    let mut s = format!("{:?}", s); // XXX
-    s.insert_str(s.len() - 1, "\\0");
-    s
+fn emit_required_features(indent: &str, required_features: &BTreeSet<String>, out: &mut impl io::Write) -> io::Result<()> {
+    match required_features.len() {
+        0 => {},
+        1 => {
+            let required_feature = required_features.iter().next().unwrap();
+            writeln!(out, "{}///", indent)?;
+            writeln!(out, "{}/// Required feature: {:?}", indent, required_feature)?;
+            writeln!(out, "{}#[cfg(any(feature = \"all\", feature = {:?}))]", indent, required_feature)?;
+        },
+        _ => { // e.g. List<String>
+            let features = required_features.iter().map(|feature| format!("{:?}", feature)).collect::<Vec<_>>();
+            let cfgs     = required_features.iter().map(|feature| format!("feature = {:?}", feature)).collect::<Vec<_>>();
+            writeln!(out, "{}///", indent)?;
+            writeln!(out, "{}/// Required features: {}", indent, features.join(", "))?;
+            writeln!(out, "{}#[cfg(any(feature = \"all\", all({})))]", indent, cfgs.join(", "))?;
+        },
+    }
+    Ok(())
 }
@@ -0,0 +1,158 @@
+use crate::class_file::*;
+use crate::emit_rust::*;
+use crate::identifiers::*;
+
+use jreflection::class;
+
+use std::collections::BTreeSet;
+
+
+
+/// The type variables in scope for `generics = "phantom"` codegen, and the Rust type parameters they're emitted as.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TypeScope {
+    vars: Vec<(String, String)>, // (java, rust) - later entries shadow earlier ones
+}
+
+impl TypeScope {
+    /// The type parameters of `class`, if it's emitted as a generic struct.
+    pub(crate) fn for_class(context: &Context, class: class::Id) -> Self {
+        let vars = struct_type_params(context, class).into_iter().map(|name| (name.to_owned(), name.to_owned())).collect();
+        Self { vars }
+    }
+
+    /// Bring a method's type parameters into scope, renaming any that would shadow a class type parameter (Rust doesn't
+    /// allow that.)  Returns the Rust names of the added type parameters.  Type parameters without a valid Rust
+    /// identifier are skipped, causing anything referencing them to fall back on erased types.
+    pub(crate) fn add_method_params(&mut self, params: &[TypeParameter]) -> Vec<String> {
+        let mut added = Vec::new();
+        for param in params {
+            if !is_valid_type_param(param.name.as_str()) { continue; }
+            let mut rust = param.name.clone();
+            let mut n = 2;
+            while self.vars.iter().any(|(_, existing)| existing == &rust) {
+                rust = format!("{}{}", param.name, n);
+                n += 1;
+            }
+            self.vars.push((param.name.clone(), rust.clone()));
+            added.push(rust);
+        }
+        added
+    }
+
+    fn get(&self, java: &str) -> Option<&str> {
+        self.vars.iter().rev().find(|(j, _)| j == java).map(|(_, rust)| rust.as_str())
+    }
+}
+
+/// The type parameters of the struct generated for `class`:  empty unless `generics = "phantom"` and `class` has a
+/// generic signature we can represent.  Structs for classes from \[[extern\]] crates are assumed to be erased.
+pub(crate) fn struct_type_params<'c>(context: &'c Context, class: class::Id) -> Vec<&'c str> {
+    if context.config.codegen.generics != config::toml::GenericsStyle::Phantom { return Vec::new(); }
+    if context.extern_for(class).is_some() { return Vec::new(); }
+    if context.class_reject_reason(class::Id("java/lang/Object")).is_some() { return Vec::new(); } // Nothing to default to
+
+    let signature = match context.known.get(class.as_str()) {
+        Some(KnownClass { bound: true, signature: Some(signature), .. }) => signature,
+        _ => return Vec::new(),
+    };
+
+    let names : Vec<&str> = signature.type_parameters.iter().map(|param| param.name.as_str()).collect();
+    let valid = names.iter().enumerate().all(|(i, name)| is_valid_type_param(name) && !names[..i].contains(name));
+    if valid { names } else { Vec::new() }
+}
+
+/// The Rust path of `java.lang.Object`, which generic type parameters default to.
+pub(crate) fn object_type(context: &Context, required_features: &mut BTreeSet<String>) -> Option<String> {
+    let object = class::Id("java/lang/Object");
+    if context.class_reject_reason(object).is_some() { return None; }
+    required_features.extend(context.required_feature_for(object).ok()?);
+    context.java_to_rust_path(object).ok()
+}
+
+/// The Rust type of a reference type from a generic signature (e.g. "java::util::List<E>"), or `None` if it can't be
+/// represented - in which case callers should fall back on the erased type from the descriptor.  Rust type parameters
+/// referenced are added to `used_params`.  Wildcards are approximated by their bound (`?` by the default, `Object`.)
+pub(crate) fn rust_type(context: &Context, scope: &TypeScope, ty: &TypeSignature, required_features: &mut BTreeSet<String>, used_params: &mut BTreeSet<String>) -> Option<String> {
+    match ty {
+        TypeSignature::Base(_) => None,
+        TypeSignature::Variable(name) => {
+            let rust = scope.get(name.as_str())?;
+            used_params.insert(rust.to_owned());
+            Some(rust.to_owned())
+        },
+        TypeSignature::Array(inner) => {
+            let element = match &**inner {
+                TypeSignature::Base('Z') => return Some("__jni_bindgen::BooleanArray".to_owned()),
+                TypeSignature::Base('B') => return Some("__jni_bindgen::ByteArray".to_owned()),
+                TypeSignature::Base('C') => return Some("__jni_bindgen::CharArray".to_owned()),
+                TypeSignature::Base('S') => return Some("__jni_bindgen::ShortArray".to_owned()),
+                TypeSignature::Base('I') => return Some("__jni_bindgen::IntArray".to_owned()),
+                TypeSignature::Base('J') => return Some("__jni_bindgen::LongArray".to_owned()),
+                TypeSignature::Base('F') => return Some("__jni_bindgen::FloatArray".to_owned()),
+                TypeSignature::Base('D') => return Some("__jni_bindgen::DoubleArray".to_owned()),
+                inner => rust_type(context, scope, inner, required_features, used_params)?,
+            };
+            Some(format!("__jni_bindgen::ObjectArray<{}, {}>", element, context.config.codegen.throwable_type.as_str()))
+        },
+        TypeSignature::Class(class) => {
+            let id = class::Id(class.path.as_str());
+            if context.class_reject_reason(id).is_some() { return None; }
+            let path = context.java_to_rust_path(id).ok()?;
+            required_features.extend(context.required_feature_for(id).ok()?);
+
+            let params = struct_type_params(context, id);
+            if params.is_empty() || params.len() != class.arguments.len() {
+                return Some(path); // Raw type (or erased struct):  type parameters (if any) use their defaults
+            }
+
+            let mut args = Vec::new();
+            for arg in &class.arguments {
+                let arg = match arg {
+                    TypeArgument::Any => None,
+                    TypeArgument::Exact(ty)     => rust_type(context, scope, ty, required_features, used_params),
+                    TypeArgument::Extends(ty)   => rust_type(context, scope, ty, required_features, used_params),
+                    TypeArgument::Super(ty)     => rust_type(context, scope, ty, required_features, used_params),
+                };
+                args.push(match arg {
+                    Some(arg) => arg,
+                    None => object_type(context, required_features)?,
+                });
+            }
+            Some(format!("{}<{}>", path, args.join(", ")))
+        },
+    }
+}
+
+fn is_valid_type_param(name: &str) -> bool {
+    match RustIdentifier::from_str(name) {
+        RustIdentifier::Identifier(id) => id == name && !name.starts_with("__"), // __R etc. are used by codegen
+        _ => false,
+    }
+}
+
+#[test] fn phantom_generics_bound_setters() {
+    use crate::util::test_class_file::*;
+
+    let dir = temp_dir("phantom");
+    let classes = dir.join("classes");
+    let object = Some("java/lang/Object");
+    write_class(&classes, "java/lang/CharSequence", &class_file(ACC_PUBLIC | ACC_INTERFACE | ACC_ABSTRACT, "java/lang/CharSequence", object, None, &[])).unwrap();
+    write_class(&classes, "p/Box", &class_file(ACC_PUBLIC | ACC_SUPER, "p/Box", object, Some("<T::Ljava/lang/CharSequence;>Ljava/lang/Object;"), &[
+        (ACC_PUBLIC, "value", "Ljava/lang/CharSequence;", Some("TT;")),
+    ])).unwrap();
+
+    let generated = generate(&dir, r#"
+        [input]
+        files = ["classes"]
+
+        [output]
+        path = "src/generated.rs"
+
+        [codegen]
+        generics = "phantom"
+    "#);
+    // `&'obj T` is only well formed if `T: 'obj`
+    assert!(generated.contains("value: impl __jni_bindgen::std::convert::Into<__jni_bindgen::std::option::Option<&'obj T>>) where T: 'obj {"), "{}", generated);
+    assert!(generated.contains("__jni_bindgen::std::option::Option<__jni_bindgen::Local<'env, T>>"), "{}", generated);
+}
@@ -1,3 +1,4 @@
+use crate::class_file::*;
 use crate::emit_rust::*;
 use crate::identifiers::*;
 
@@ -11,15 +12,30 @@ use std::io;
 pub struct Method<'a> {
     pub class:      &'a jreflection::Class,
     pub java:       &'a jreflection::Method,
+    signature:      Option<MethodSignature>,
+    parameter_names:Vec<Option<String>>,
+    nullability:    Option<Nullability>,
     rust_name:      Option<String>,
     mangling_style: MethodManglingStyle,
 }
 
 impl<'a> Method<'a> {
-    pub fn new(context: &Context, class: &'a jreflection::Class, java: &'a jreflection::Method) -> Self {
+    pub fn new(context: &Context, class: &'a jreflection::Class, attributes: &ClassAttributes, java: &'a jreflection::Method) -> Self {
+        let member = attributes.method(java.name.as_str(), java.descriptor_str());
+        let signature = if context.config.codegen.generics == config::toml::GenericsStyle::Phantom {
+            member.and_then(|m| m.signature.as_ref()).and_then(|s| MethodSignature::parse(s.as_str()))
+        } else {
+            None
+        };
+        let parameter_names = member.map_or(Vec::new(), |m| m.parameter_names.clone());
+        let nullability = member.and_then(|m| m.nullability);
+
         let mut result = Self {
             class,
             java,
+            signature,
+            parameter_names,
+            nullability,
             rust_name:      None,
             mangling_style: MethodManglingStyle::Java, // Immediately overwritten bellow
         };
@@ -40,24 +56,46 @@ impl<'a> Method<'a> {
         };
     }
 
+    /// Rust names for each parameter:  the mangled Java names where debug info has them, `argN` otherwise - or if
+    /// the Java name can't be mangled, or would collide with another parameter or the names generated code uses.
+    pub(crate) fn parameter_names(&self) -> Vec<String> {
+        let count = self.java.descriptor().arguments().count();
+        let java_names = if self.parameter_names.len() == count { &self.parameter_names[..] } else { &[][..] }; // Synthetic parameters etc.
+        let rustify = match self.mangling_style {
+            MethodManglingStyle::Java | MethodManglingStyle::JavaShortSignature | MethodManglingStyle::JavaLongSignature           => false,
+            MethodManglingStyle::Rustify | MethodManglingStyle::RustifyShortSignature | MethodManglingStyle::RustifyLongSignature   => true,
+        };
+
+        let mut names = Vec::<String>::with_capacity(count);
+        for idx in 0..count {
+            let name = java_names.get(idx).and_then(|name| name.as_ref()).and_then(|name| {
+                if rustify { rustify_identifier(name).ok() } else { javaify_identifier(name).ok() }
+            });
+            let name = match name {
+                // Uppercase names could refer to structs in scope, which parameters can't shadow
+                Some(name) if !name.starts_with("__jni") && !name.starts_with(|ch: char| ch.is_ascii_uppercase()) && name != "this" && name != "_class" && !names.contains(&name) => name,
+                _ => {
+                    let mut name = format!("arg{}", idx);
+                    while names.contains(&name) { name.push('_'); }
+                    name
+                },
+            };
+            names.push(name);
+        }
+        names
+    }
+
     pub fn emit(&self, context: &Context, indent: &str, out: &mut impl io::Write) -> io::Result<()> {
         let mut emit_reject_reasons = Vec::new();
         let mut required_features = BTreeSet::new();
 
-        let java_class_method       = format!("{}\x1f{}", self.class.path.as_str(), &self.java.name);
-        let java_class_method_sig   = format!("{}\x1f{}\x1f{}", self.class.path.as_str(), &self.java.name, self.java.descriptor_str());
-
-        let ignored =
-            context.config.ignore_class_methods    .contains(&java_class_method) ||
-            context.config.ignore_class_method_sigs.contains(&java_class_method_sig);
-
-        let renamed_to = context.config.rename_class_methods    .get(&java_class_method)
-            .or_else(||  context.config.rename_class_method_sigs.get(&java_class_method_sig));
+        let ignored     = context.config.ignore_class_method(self.class.path.as_str(), &self.java.name, self.java.descriptor_str());
+        let renamed_to  = context.config.rename_class_method(self.class.path.as_str(), &self.java.name, self.java.descriptor_str());
 
         let descriptor = self.java.descriptor();
 
         let method_name = if let Some(renamed_to) = renamed_to {
-            renamed_to.clone()
+            renamed_to
         } else if let Some(name) = self.rust_name() {
             name.to_owned()
         } else {
@@ -72,13 +110,19 @@ impl<'a> Method<'a> {
 
         // Parameter names may or may not be available as extra debug information.  Example:
         // https://docs.oracle.com/javase/tutorial/reflect/member/methodparameterreflection.html
+        let arg_names = self.parameter_names();
 
         let mut params_array = String::new(); // Contents of let __jni_args = [...];
 
+        // static_env = "implicit":  No env parameter, so results can't borrow one - return Globals instead of Locals.
+        let implicit_env = (self.java.is_constructor() || self.java.is_static()) && context.config.codegen.static_env == config::toml::StaticEnvStyle::Implicit;
+        let local = if implicit_env { "__jni_bindgen::Global<" } else { "__jni_bindgen::Local<'env, " };
+
         // Contents of fn name<'env>(...) {
         let mut params_decl = if self.java.is_constructor() || self.java.is_static() {
             match context.config.codegen.static_env {
                 config::toml::StaticEnvStyle::Explicit => String::from("__jni_env: &'env __jni_bindgen::Env"),
+                config::toml::StaticEnvStyle::Implicit => String::new(),
                 config::toml::StaticEnvStyle::__NonExhaustive => {
                     emit_reject_reasons.push("ERROR:  StaticEnvStyle::__NonExhaustive is invalid, silly goose!");
                     String::new()
@@ -88,12 +132,28 @@ impl<'a> Method<'a> {
             String::from("&'env self")
         };
 
+        // generics = "phantom":  Use the generic signature where it's consistent with the descriptor.  Inner class
+        // constructors etc. have synthetic parameters that the generic signature omits, so we play it safe and ignore those.
+        let mut type_scope = if self.java.is_static() { TypeScope::default() } else { TypeScope::for_class(context, self.class.path.as_id()) };
+        let mut used_type_params = BTreeSet::new();
+        let (method_type_params, generic_args, generic_ret) = match self.signature.as_ref() {
+            Some(signature) if signature.arguments.len() == descriptor.arguments().count() => {
+                (type_scope.add_method_params(&signature.type_parameters[..]), &signature.arguments[..], signature.return_type.as_ref())
+            },
+            _ => (Vec::new(), &[][..], None),
+        };
+
         for (arg_idx, arg) in descriptor.arguments().enumerate() {
-            let arg_name = format!("arg{}", arg_idx);
+            let arg_name = &arg_names[arg_idx];
 
             let mut param_is_object = false; // XXX
 
-            let arg_type = match arg {
+            let generic_type = generic_args.get(arg_idx).and_then(|ty| rust_type(context, &type_scope, ty, &mut required_features, &mut used_type_params));
+
+            let arg_type = if let Some(generic_type) = generic_type {
+                param_is_object = true;
+                format!("impl __jni_bindgen::std::convert::Into<__jni_bindgen::std::option::Option<&'env {}>>", generic_type)
+            } else { match arg {
                 method::Type::Single(method::BasicType::Void) => {
                     emit_reject_reasons.push("ERROR:  Void arguments aren't a thing");
                     "()".to_owned()
@@ -107,8 +167,9 @@ impl<'a> Method<'a> {
                 method::Type::Single(method::BasicType::Float)       => "f32".to_owned(),
                 method::Type::Single(method::BasicType::Double)      => "f64".to_owned(),
                 method::Type::Single(method::BasicType::Class(class)) => {
-                    if let Ok(feature) = Struct::feature_for(context, class) {
-                        required_features.insert(feature);
+                    if let Some(reason) = context.class_reject_reason(class) { emit_reject_reasons.push(reason); }
+                    if let Ok(feature) = context.required_feature_for(class) {
+                        required_features.extend(feature);
                     } else {
                         emit_reject_reasons.push("ERROR:  Unable to resolve class feature");
                     }
@@ -136,8 +197,9 @@ impl<'a> Method<'a> {
                         method::BasicType::Float        => buffer.push_str("__jni_bindgen::FloatArray"),
                         method::BasicType::Double       => buffer.push_str("__jni_bindgen::DoubleArray"),
                         method::BasicType::Class(class) => {
-                            if let Ok(feature) = Struct::feature_for(context, class) {
-                                required_features.insert(feature);
+                            if let Some(reason) = context.class_reject_reason(class) { emit_reject_reasons.push(reason); }
+                            if let Ok(feature) = context.required_feature_for(class) {
+                                required_features.extend(feature);
                             } else {
                                 emit_reject_reasons.push("ERROR:  Unable to resolve class feature");
                             }
@@ -168,7 +230,7 @@ impl<'a> Method<'a> {
                     param_is_object = true;
                     buffer
                 },
-            };
+            }};
 
             if !params_array.is_empty() {
                 params_array.push_str(", ");
@@ -189,7 +251,16 @@ impl<'a> Method<'a> {
             params_decl.push_str(arg_type.as_str());
         }
 
-        let mut ret_decl = match descriptor.return_type() { // Contents of fn name<'env>() -> Result<...> {
+        let generic_ret = generic_ret.and_then(|ty| rust_type(context, &type_scope, ty, &mut required_features, &mut used_type_params));
+
+        // nullability_annotations = true:  Methods annotated @NonNull return Local<T> instead of Option<Local<T>>.
+        let non_null_ret = context.config.codegen.nullability_annotations && self.nullability == Some(Nullability::NonNull) && !self.java.is_constructor() && matches!(descriptor.return_type(),
+            method::Type::Single(method::BasicType::Class(_)) | method::Type::Array { .. });
+        let (option, option_end) = if non_null_ret { ("", "") } else { ("__jni_bindgen::std::option::Option<", ">") };
+
+        let mut ret_decl = if let Some(generic_ret) = generic_ret {
+            format!("{}{}{}>{}", option, local, generic_ret, option_end)
+        } else { match descriptor.return_type() { // Contents of fn name<'env>() -> Result<...> {
             method::Type::Single(method::BasicType::Void)        => "()".to_owned(),
             method::Type::Single(method::BasicType::Boolean)     => "bool".to_owned(),
             method::Type::Single(method::BasicType::Byte)        => "i8".to_owned(),
@@ -200,13 +271,14 @@ impl<'a> Method<'a> {
             method::Type::Single(method::BasicType::Float)       => "f32".to_owned(),
             method::Type::Single(method::BasicType::Double)      => "f64".to_owned(),
             method::Type::Single(method::BasicType::Class(class)) => {
-                if let Ok(feature) = Struct::feature_for(context, class) {
-                    required_features.insert(feature);
+                if let Some(reason) = context.class_reject_reason(class) { emit_reject_reasons.push(reason); }
+                if let Ok(feature) = context.required_feature_for(class) {
+                    required_features.extend(feature);
                 } else {
                     emit_reject_reasons.push("ERROR:  Unable to resolve class feature");
                 }
                 match context.java_to_rust_path(class) {
-                    Ok(path) => format!("__jni_bindgen::std::option::Option<__jni_bindgen::Local<'env, {}>>", path),
+                    Ok(path) => format!("{}{}{}>{}", option, local, path, option_end),
                     Err(_) => {
                         emit_reject_reasons.push("ERROR:  Failed to resolve JNI path to Rust path for return type");
                         format!("{:?}", class)
@@ -218,7 +290,7 @@ impl<'a> Method<'a> {
                 "???".to_owned()
             }
             method::Type::Array { levels, inner } => {
-                let mut buffer = "__jni_bindgen::std::option::Option<__jni_bindgen::Local<'env, ".to_owned();
+                let mut buffer = format!("{}{}", option, local);
                 for _ in 0..(levels-1) {
                     buffer.push_str("__jni_bindgen::ObjectArray<");
                 }
@@ -232,8 +304,9 @@ impl<'a> Method<'a> {
                     method::BasicType::Float        => buffer.push_str("__jni_bindgen::FloatArray"),
                     method::BasicType::Double       => buffer.push_str("__jni_bindgen::DoubleArray"),
                     method::BasicType::Class(class) => {
-                        if let Ok(feature) = Struct::feature_for(context, class) {
-                            required_features.insert(feature);
+                        if let Some(reason) = context.class_reject_reason(class) { emit_reject_reasons.push(reason); }
+                        if let Ok(feature) = context.required_feature_for(class) {
+                            required_features.extend(feature);
                         } else {
                             emit_reject_reasons.push("ERROR:  Unable to resolve class feature");
                         }
@@ -259,10 +332,11 @@ impl<'a> Method<'a> {
                     buffer.push_str(context.config.codegen.throwable_type.as_str());
                     buffer.push_str(">");
                 }
-                buffer.push_str(">>"); // Local, Option
+                buffer.push_str(">"); // Local
+                buffer.push_str(option_end);
                 buffer
             },
-        };
+        }};
 
         let mut ret_method_fragment = match descriptor.return_type() { // Contents of call_..._method_a
             method::Type::Single(method::BasicType::Void)        => "void",
@@ -281,8 +355,10 @@ impl<'a> Method<'a> {
         if self.java.is_constructor() {
             if descriptor.return_type() == method::Type::Single(method::BasicType::Void) {
                 ret_method_fragment = "object";
+                let type_params = struct_type_params(context, self.class.path.as_id());
                 ret_decl = match context.java_to_rust_path(self.class.path.as_id()) {
-                    Ok(path) => format!("__jni_bindgen::Local<'env, {}>", path),
+                    Ok(path) if !type_params.is_empty() => format!("{}{}<{}>>", local, path, type_params.join(", ")),
+                    Ok(path) => format!("{}{}>", local, path),
                     Err(_) => {
                         emit_reject_reasons.push("ERROR:  Failed to resolve JNI path to Rust path for this type");
                         format!("{:?}", self.class.path.as_str())
@@ -293,6 +369,7 @@ impl<'a> Method<'a> {
             }
         }
 
+        emit_reject_reasons.dedup(); // e.g. several arguments of the same unresolvable type
         let emit_reject_reasons = emit_reject_reasons; // Freeze
         let indent = if emit_reject_reasons.is_empty() {
             format!("{}        ", indent)
@@ -340,36 +417,56 @@ impl<'a> Method<'a> {
             }
             writeln!(out, ")))]")?;
         }
-        writeln!(out, "{}{}{}fn {}<'env>({}) -> __jni_bindgen::std::result::Result<{}, __jni_bindgen::Local<'env, {}>> {{", indent, attributes, access, method_name, params_decl, ret_decl, context.config.codegen.throwable_type.as_str())?;
+        let mut generics_decl = String::from("'env"); // Contents of fn name<...>(
+        for param in method_type_params.iter().filter(|param| used_type_params.contains(param.as_str())) {
+            generics_decl.push_str(", ");
+            generics_decl.push_str(param.as_str());
+            generics_decl.push_str(": __jni_bindgen::AsValidJObjectAndEnv + 'env");
+        }
+
+        let mut where_clause = String::new(); // Locals imply `E: 'env` for Local<'env, Foo<E>>, Globals don't
+        if implicit_env && self.java.is_constructor() {
+            for (idx, param) in struct_type_params(context, self.class.path.as_id()).iter().enumerate() {
+                where_clause.push_str(if idx == 0 { " where " } else { ", " });
+                where_clause.push_str(param);
+                where_clause.push_str(": 'env");
+            }
+        }
+
+        writeln!(out, "{}{}{}fn {}<{}>({}) -> __jni_bindgen::std::result::Result<{}, {}{}>>{} {{", indent, attributes, access, method_name, generics_decl, params_decl, ret_decl, local, context.config.codegen.throwable_type.as_str(), where_clause)?;
         writeln!(out, "{}    // class.path == {:?}, java.flags == {:?}, .name == {:?}, .descriptor == {:?}", indent, &self.class.path.as_str(), self.java.flags, &self.java.name, &self.java.descriptor_str())?;
         writeln!(out, "{}    unsafe {{", indent)?;
         writeln!(out, "{}        let __jni_args = [{}];", indent, params_array)?;
+        let body_indent = if implicit_env { format!("{}    ", indent) } else { indent.clone() };
         if self.java.is_constructor() || self.java.is_static() {
             match context.config.codegen.static_env {
                 config::toml::StaticEnvStyle::Explicit          => {},
+                config::toml::StaticEnvStyle::Implicit          => writeln!(out, "{}        __jni_bindgen::VM::with_implicit_env(|__jni_env| {{", indent)?,
                 config::toml::StaticEnvStyle::__NonExhaustive   => writeln!(out, "{}    let __jni_env = ...?;", indent)?, // XXX
             };
         } else {
             writeln!(out, "{}        let __jni_env = __jni_bindgen::Env::from_ptr(self.0.env);", indent)?;
         }
 
-        writeln!(out, "{}        let (__jni_class, __jni_method) = __jni_env.require_class_{}method({}, {}, {});", indent, if self.java.is_static() { "static_" } else { "" }, emit_cstr(self.class.path.as_str()), emit_cstr(self.java.name.as_str()), emit_cstr(self.java.descriptor_str()) )?;
+        writeln!(out, "{}        static __JNI_METHOD : __jni_bindgen::IdCache = __jni_bindgen::IdCache::new();", body_indent)?;
+        writeln!(out, "{}        let (__jni_class, __jni_method) = __JNI_METHOD.require_class_{}method(__jni_env, {}, {}, {});", body_indent, if self.java.is_static() { "static_" } else { "" }, emit_cstr(self.class.path.as_str()), emit_cstr(self.java.name.as_str()), emit_cstr(self.java.descriptor_str()) )?;
 
-        if self.java.is_constructor() {
-            writeln!(out, "{}        __jni_env.new_object_a(__jni_class, __jni_method, __jni_args.as_ptr())", indent)?;
+        let call = if self.java.is_constructor() {
+            "__jni_env.new_object_a(__jni_class, __jni_method, __jni_args.as_ptr())".to_owned()
         } else if self.java.is_static() {
-            writeln!(out, "{}        __jni_env.call_static_{}_method_a(__jni_class, __jni_method, __jni_args.as_ptr())", indent, ret_method_fragment)?;
+            format!("__jni_env.call_static_{}_method_a(__jni_class, __jni_method, __jni_args.as_ptr())", ret_method_fragment)
         } else {
-            writeln!(out, "{}        __jni_env.call_{}_method_a(self.0.object, __jni_method, __jni_args.as_ptr())", indent, ret_method_fragment)?;
+            format!("__jni_env.call_{}_method_a(self.0.object, __jni_method, __jni_args.as_ptr())", ret_method_fragment)
+        };
+        let call = if non_null_ret { format!("__jni_env.require_non_null({})", call) } else { call };
+        if implicit_env {
+            writeln!(out, "{}        __jni_bindgen::Unlocal::unlocal({})", body_indent, call)?;
+            writeln!(out, "{}        }})", indent)?;
+        } else {
+            writeln!(out, "{}        {}", body_indent, call)?;
         }
         writeln!(out, "{}    }}", indent)?;
         writeln!(out, "{}}}", indent)?;
         Ok(())
     }
 }
-
-fn emit_cstr(s: &str) -> String {
-    let mut s = format!("{:?}", s); // XXX
-    s.insert_str(s.len() - 1, "\\0");
-    s
-}
@@ -11,6 +11,25 @@ pub(crate) struct Module {
 }
 
 impl Module {
+    pub(crate) fn visit_structs<'m>(&'m self, visitor: &mut impl FnMut(&'m Struct)) {
+        for module in self.modules.values() { module.visit_structs(visitor); }
+        for structure in self.structs.values() { visitor(structure); }
+    }
+
+    /// The module at `scope` (as returned by [StructPaths::local_scope]), if there is one.
+    pub(crate) fn find<'s>(&self, scope: impl Iterator<Item = &'s str>) -> Option<&Module> {
+        let mut module = self;
+        for fragment in scope { module = module.modules.get(fragment)?; }
+        Some(module)
+    }
+
+    /// Remove structs for which `keep` returns false, and any modules left empty.
+    pub(crate) fn retain_structs(&mut self, keep: &mut impl FnMut(&Struct) -> bool) {
+        for module in self.modules.values_mut() { module.retain_structs(keep); }
+        self.modules.retain(|_, module| !module.structs.is_empty() || !module.modules.is_empty());
+        self.structs.retain(|_, structure| keep(structure));
+    }
+
     pub(crate) fn write(&self, context: &Context, indent: &str, out: &mut impl Write) -> io::Result<()> {
         let next_indent = format!("{}    ", indent);
 
@@ -0,0 +1,269 @@
+use crate::emit_rust::*;
+use crate::identifiers::*;
+
+use jreflection::method;
+
+use std::collections::*;
+use std::error::Error;
+use std::io;
+
+
+
+/// The Java `native` methods of a class, emitted as a Rust trait for the user to implement, plus a macro exporting an
+/// implementation of that trait under the mangled `Java_...` symbol names the JVM will look for.
+pub struct Natives<'a> {
+    class:      &'a Struct,
+    methods:    Vec<Method<'a>>,
+}
+
+struct NativeParam {
+    trait_type:     String, // e.g. "bool", "__jni_bindgen::std::option::Option<__jni_bindgen::Ref<'env, crate::Foo>>"
+    export_type:    String, // e.g. "$crate::__jni_bindgen::jni_sys::jboolean", "$crate::__jni_bindgen::Argument<$crate::Foo>"
+    is_boolean:     bool,
+    is_object:      bool,
+}
+
+impl<'a> Natives<'a> {
+    pub fn new(context: &Context, class: &'a Struct) -> Self {
+        let mut methods : Vec<Method> = class.java.methods.iter().filter(|m| m.is_native()).map(|m| Method::new(context, &class.java, &class.attributes, m)).collect();
+
+        let mut id_repeats = HashMap::new();
+        for method in &methods {
+            if let Some(name) = method.rust_name() {
+                *id_repeats.entry(name.to_owned()).or_insert(0) += 1;
+            }
+        }
+        for method in &mut methods {
+            let overloaded = method.rust_name().map_or(false, |name| id_repeats[name] > 1);
+            if overloaded {
+                method.set_mangling_style(context.config.codegen.method_naming_style_collision);
+            }
+        }
+
+        Self { class, methods }
+    }
+
+    pub fn trait_name(&self) -> String { format!("{}Natives", self.class.rust.struct_name) }
+    pub fn macro_name(&self) -> String { format!("export_{}_natives", self.class.rust.feature_name.replace("-", "_")) }
+
+    pub fn emit(&self, context: &Context, indent: &str, out: &mut impl io::Write) -> io::Result<()> {
+        if !context.config.codegen.native_traits || self.methods.is_empty() { return Ok(()); }
+
+        let mut required_features = BTreeSet::new();
+        required_features.insert(self.class.rust.feature_name.clone());
+
+        let mut trait_fns  = Vec::new();
+        let mut export_fns = Vec::new();
+
+        let mut name_repeats = HashMap::new();
+        for method in &self.methods {
+            *name_repeats.entry(method.java.name.as_str()).or_insert(0) += 1;
+        }
+
+        for method in &self.methods {
+            match self.native(context, method, name_repeats[method.java.name.as_str()] > 1, &mut required_features) {
+                Ok((trait_fn, export_fn)) => {
+                    trait_fns.push(trait_fn);
+                    export_fns.push(export_fn);
+                },
+                Err(reason) => trait_fns.push(format!("// Not emitting: {}: {}", method.java.name.as_str(), reason)),
+            }
+        }
+
+        let mut cfg = format!("{}#[cfg(any(feature = \"all\", all(", indent);
+        for (idx, feature) in required_features.iter().enumerate() {
+            if idx != 0 { cfg.push_str(", "); }
+            cfg.push_str(&format!("feature = {:?}", feature));
+        }
+        cfg.push_str(")))]");
+
+        let trait_name = self.trait_name();
+        let macro_name = self.macro_name();
+
+        writeln!(out, "")?;
+        writeln!(out, "{}/// Java `native` methods of [{}](struct.{}.html), to be implemented in Rust.", indent, &self.class.rust.struct_name, &self.class.rust.struct_name)?;
+        writeln!(out, "{}///", indent)?;
+        writeln!(out, "{}/// Export your implementation with `{}!(YourType);`.", indent, &macro_name)?;
+        writeln!(out, "{}", &cfg)?;
+        writeln!(out, "{}pub trait {} {{", indent, &trait_name)?;
+        for trait_fn in &trait_fns {
+            for line in trait_fn.lines() {
+                writeln!(out, "{}    {}", indent, line)?;
+            }
+        }
+        writeln!(out, "{}}}", indent)?;
+
+        writeln!(out, "")?;
+        writeln!(out, "{}/// Exports `$natives`' implementation of [{}](trait.{}.html) as `#[no_mangle] extern \"system\" fn Java_...`", indent, &trait_name, &trait_name)?;
+        writeln!(out, "{}/// symbols, for the JVM to find when the Java `native` methods are first invoked.", indent)?;
+        writeln!(out, "{}", &cfg)?;
+        writeln!(out, "{}#[macro_export]", indent)?;
+        writeln!(out, "{}macro_rules! {} {{", indent, &macro_name)?;
+        writeln!(out, "{}    ($natives:ty) => {{", indent)?;
+        for export_fn in &export_fns {
+            for line in export_fn.lines() {
+                writeln!(out, "{}        {}", indent, line)?;
+            }
+        }
+        writeln!(out, "{}    }};", indent)?;
+        writeln!(out, "{}}}", indent)?;
+        Ok(())
+    }
+
+    fn native(&self, context: &Context, method: &Method, overloaded: bool, required_features: &mut BTreeSet<String>) -> Result<(String, String), Box<dyn Error>> {
+        let rust_name = method.rust_name().ok_or("Failed to mangle method name")?;
+        let descriptor = method.java.descriptor();
+        let symbol = jni_native_symbol(self.class.java.path.as_str(), method.java.name.as_str(), if overloaded { Some(method.java.descriptor_str()) } else { None });
+        let trait_path = format!("{}{}", self.class.rust.mod_prefix, self.trait_name());
+        let struct_path = format!("{}{}", self.class.rust.mod_prefix, self.class.rust.struct_name);
+
+        let mut trait_params  = String::from("__jni_env: &'env __jni_bindgen::Env");
+        let mut export_params = String::from("__jni_env: &$crate::__jni_bindgen::Env");
+        let mut call_args     = String::from("__jni_env");
+        if method.java.is_static() {
+            export_params.push_str(", _class: $crate::__jni_bindgen::jni_sys::jclass");
+        } else {
+            trait_params.push_str(&format!(", this: __jni_bindgen::Ref<'env, {}>", &self.class.rust.struct_name));
+            export_params.push_str(&format!(", this: $crate::__jni_bindgen::Argument<{}>", dollar_crate(&struct_path)));
+            call_args.push_str(", this.with_unchecked(__jni_env).unwrap()");
+        }
+
+        for (arg, arg_name) in descriptor.arguments().zip(method.parameter_names()) {
+            let param = native_param(context, arg, required_features)?;
+            trait_params .push_str(&format!(", {}: {}", arg_name, &param.trait_type));
+            export_params.push_str(&format!(", {}: {}", arg_name, &param.export_type));
+            if param.is_boolean {
+                call_args.push_str(&format!(", {} != $crate::__jni_bindgen::jni_sys::JNI_FALSE", arg_name));
+            } else if param.is_object {
+                call_args.push_str(&format!(", {}.with_unchecked(__jni_env)", arg_name));
+            } else {
+                call_args.push_str(&format!(", {}", arg_name));
+            }
+        }
+
+        let call = format!("<$natives as {}>::{}({})", dollar_crate(&trait_path), rust_name, &call_args);
+        let (trait_ret, export_ret, export_body) = match descriptor.return_type() {
+            method::Type::Single(method::BasicType::Void) => (String::new(), String::new(), call),
+            method::Type::Single(method::BasicType::Boolean) => (
+                " -> bool".to_owned(),
+                " -> $crate::__jni_bindgen::jni_sys::jboolean".to_owned(),
+                format!("if {} {{ $crate::__jni_bindgen::jni_sys::JNI_TRUE }} else {{ $crate::__jni_bindgen::jni_sys::JNI_FALSE }}", call),
+            ),
+            ret => {
+                let param = native_param(context, ret, required_features)?;
+                if param.is_object {
+                    (
+                        format!(" -> __jni_bindgen::std::option::Option<__jni_bindgen::Local<'env, {}>>", object_type(context, ret, required_features)?),
+                        " -> $crate::__jni_bindgen::jni_sys::jobject".to_owned(),
+                        format!("{}.map_or($crate::__jni_bindgen::std::ptr::null_mut(), $crate::__jni_bindgen::Local::into_raw)", call),
+                    )
+                } else {
+                    (format!(" -> {}", param.trait_type), format!(" -> {}", param.export_type), call)
+                }
+            },
+        };
+
+        let mut trait_fn = String::new();
+        if let Some(url) = KnownDocsUrl::from_method(context, method) {
+            trait_fn.push_str(&format!("/// {}\n", url));
+        } else {
+            trait_fn.push_str(&format!("/// {}\n", method.java.name.as_str()));
+        }
+        trait_fn.push_str(&format!("fn {}<'env>({}){};", rust_name, trait_params, trait_ret));
+
+        let export_fn = format!(
+            "#[no_mangle] #[allow(non_snake_case)] pub extern \"system\" fn {}({}){} {{\n    unsafe {{ {} }}\n}}",
+            symbol, export_params, export_ret, export_body);
+
+        Ok((trait_fn, export_fn))
+    }
+}
+
+fn native_param(context: &Context, ty: method::Type, required_features: &mut BTreeSet<String>) -> Result<NativeParam, Box<dyn Error>> {
+    let primitive = |rust: &str| NativeParam { trait_type: rust.to_owned(), export_type: rust.to_owned(), is_boolean: false, is_object: false };
+    Ok(match ty {
+        method::Type::Single(method::BasicType::Void)       => io_data_err!("Void arguments aren't a thing")?,
+        method::Type::Single(method::BasicType::Boolean)    => NativeParam {
+            trait_type:     "bool".to_owned(),
+            export_type:    "$crate::__jni_bindgen::jni_sys::jboolean".to_owned(),
+            is_boolean:     true,
+            is_object:      false,
+        },
+        method::Type::Single(method::BasicType::Byte)       => primitive("i8"),
+        method::Type::Single(method::BasicType::Char)       => NativeParam { export_type: "$crate::__jni_bindgen::jchar".to_owned(), ..primitive("__jni_bindgen::jchar") },
+        method::Type::Single(method::BasicType::Short)      => primitive("i16"),
+        method::Type::Single(method::BasicType::Int)        => primitive("i32"),
+        method::Type::Single(method::BasicType::Long)       => primitive("i64"),
+        method::Type::Single(method::BasicType::Float)      => primitive("f32"),
+        method::Type::Single(method::BasicType::Double)     => primitive("f64"),
+        ty => {
+            let object = object_type(context, ty, required_features)?;
+            NativeParam {
+                trait_type:     format!("__jni_bindgen::std::option::Option<__jni_bindgen::Ref<'env, {}>>", &object),
+                export_type:    format!("$crate::__jni_bindgen::Argument<{}>", dollar_crate(&object)),
+                is_boolean:     false,
+                is_object:      true,
+            }
+        },
+    })
+}
+
+fn object_type(context: &Context, ty: method::Type, required_features: &mut BTreeSet<String>) -> Result<String, Box<dyn Error>> {
+    let (levels, inner) = match ty {
+        method::Type::Single(inner @ method::BasicType::Class(_))   => (0, inner),
+        method::Type::Single(_)                                     => io_data_err!("Not an object type")?,
+        method::Type::Array { levels, inner }                       => (levels, inner),
+    };
+
+    let throwable = context.config.codegen.throwable_type.as_str();
+    let mut buffer = String::new();
+    for _ in 1..levels {
+        buffer.push_str("__jni_bindgen::ObjectArray<");
+    }
+    match inner {
+        method::BasicType::Boolean      => buffer.push_str("__jni_bindgen::BooleanArray"),
+        method::BasicType::Byte         => buffer.push_str("__jni_bindgen::ByteArray"),
+        method::BasicType::Char         => buffer.push_str("__jni_bindgen::CharArray"),
+        method::BasicType::Short        => buffer.push_str("__jni_bindgen::ShortArray"),
+        method::BasicType::Int          => buffer.push_str("__jni_bindgen::IntArray"),
+        method::BasicType::Long         => buffer.push_str("__jni_bindgen::LongArray"),
+        method::BasicType::Float        => buffer.push_str("__jni_bindgen::FloatArray"),
+        method::BasicType::Double       => buffer.push_str("__jni_bindgen::DoubleArray"),
+        method::BasicType::Void         => io_data_err!("Arrays of void isn't a thing")?,
+        method::BasicType::Class(class) => {
+            if let Some(reason) = context.class_reject_reason(class) { io_data_err!("{}", reason)?; }
+            required_features.extend(context.required_feature_for(class)?);
+            let path = context.java_to_rust_path(class)?;
+            if levels == 0 {
+                buffer.push_str(&path);
+            } else {
+                buffer.push_str(&format!("__jni_bindgen::ObjectArray<{}, {}>", path, throwable));
+            }
+        },
+    }
+    for _ in 1..levels {
+        buffer.push_str(&format!(", {}>", throwable));
+    }
+    Ok(buffer)
+}
+
+/// Rewrites `crate::` and `__jni_bindgen::` paths to be usable from within a `#[macro_export]`ed macro.
+fn dollar_crate(path: &str) -> String {
+    let mut buffer = String::new();
+    let mut rest = path;
+    while !rest.is_empty() {
+        let at_ident_start = !buffer.chars().last().map_or(false, |ch| ch.is_alphanumeric() || ch == '_' || ch == ':' || ch == '$');
+        if at_ident_start && (rest.starts_with("crate::") || rest.starts_with("__jni_bindgen::")) {
+            buffer.push_str(if rest.starts_with("crate::") { "$" } else { "$crate::" });
+        }
+        let ch = rest.chars().next().unwrap();
+        buffer.push(ch);
+        rest = &rest[ch.len_utf8()..];
+    }
+    buffer
+}
+
+#[test] fn dollar_crate_test() {
+    assert_eq!(dollar_crate("crate::java::lang::Object"), "$crate::java::lang::Object");
+    assert_eq!(dollar_crate("__jni_bindgen::ObjectArray<crate::Foo, my_crate::Throwable>"), "$crate::__jni_bindgen::ObjectArray<$crate::Foo, my_crate::Throwable>");
+}
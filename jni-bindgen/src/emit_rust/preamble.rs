@@ -1,7 +1,15 @@
 use std::io::{self, Write};
 
-pub fn write_preamble(out: &mut impl Write) -> io::Result<()> {
+pub fn write_preamble(out: &mut impl Write, native_traits: bool) -> io::Result<()> {
     write!(out, "{}", include_str!("preamble-contents.rs"))?;
+    if native_traits {
+        // pub for use by #[macro_export]ed native method exporters (via $crate::__jni_bindgen)
+        writeln!(out, "#[allow(unused_imports)] #[macro_use(__jni_bindgen)] #[doc(hidden)] pub extern crate jni_glue;")?;
+        writeln!(out, "#[doc(hidden)] pub use jni_glue as __jni_bindgen;")?;
+    } else {
+        writeln!(out, "#[allow(unused_imports)] #[macro_use(__jni_bindgen)] extern crate jni_glue;")?;
+        writeln!(out, "#[doc(hidden)] use jni_glue as __jni_bindgen;")?;
+    }
     writeln!(out, "")?;
     writeln!(out, "")?;
     Ok(())
@@ -1,3 +1,4 @@
+use crate::class_file::*;
 use crate::emit_rust::*;
 use crate::identifiers::*;
 
@@ -37,8 +38,9 @@ impl StructPaths {
 
 #[derive(Debug, Default)]
 pub(crate) struct Struct {
-    pub rust:   StructPaths,
-    pub java:   jreflection::Class,
+    pub rust:       StructPaths,
+    pub java:       jreflection::Class,
+    pub attributes: ClassAttributes,
 }
 
 fn rust_id<'a>(id: &str) -> Result<&str, Box<dyn Error>> {
@@ -61,7 +63,8 @@ fn feature_id<'a>(id: &str) -> Result<&str, Box<dyn Error>> {
 
 impl Struct {
     pub(crate) fn feature_for(context: &Context, class: class::Id) -> Result<String, Box<dyn Error>> {
-        let rename_to = context.config.rename_classes.get(class.as_str()).map(|name| name.as_str()).ok_or(());
+        let rename_to = context.config.rename_class(class.as_str());
+        let rename_to = rename_to.as_deref().ok_or(());
         let mut buf = String::new();
         for component in class.iter() {
             match component {
@@ -73,8 +76,17 @@ impl Struct {
         Ok(buf)
     }
 
-    pub(crate) fn mod_for(_context: &Context, class: class::Id) -> Result<String, Box<dyn Error>> {
-        let mut buf = String::from("crate");
+    /// The Rust path prefix for `class`:  "crate::" unless it's mapped onto an existing crate via \[[extern\]].
+    fn crate_for(context: &Context, class: class::Id) -> String {
+        match context.extern_for(class) {
+            Some(ext)   => ext.rust_prefix.clone(),
+            None        => String::from("crate::"),
+        }
+    }
+
+    pub(crate) fn mod_for(context: &Context, class: class::Id) -> Result<String, Box<dyn Error>> {
+        let mut buf = Self::crate_for(context, class);
+        buf.truncate(buf.trim_end_matches("::").len());
         for component in class.iter() {
             match component {
                 class::IdPart::Namespace(id)        => write!(&mut buf, "::{}", rust_id(id)?)?,
@@ -86,7 +98,8 @@ impl Struct {
     }
 
     pub(crate) fn name_for(context: &Context, class: class::Id) -> Result<String, Box<dyn Error>> {
-        let rename_to = context.config.rename_classes.get(class.as_str()).map(|name| name.as_str()).ok_or(());
+        let rename_to = context.config.rename_class(class.as_str());
+        let rename_to = rename_to.as_deref().ok_or(());
         let mut buf = String::new();
         for component in class.iter() {
             match component {
@@ -99,8 +112,9 @@ impl Struct {
     }
 
     pub(crate) fn fqn_for(context: &Context, class: class::Id) -> Result<String, Box<dyn Error>> {
-        let rename_to = context.config.rename_classes.get(class.as_str()).map(|name| name.as_str()).ok_or(());
-        let mut buf = String::from("crate::");
+        let rename_to = context.config.rename_class(class.as_str());
+        let rename_to = rename_to.as_deref().ok_or(());
+        let mut buf = Self::crate_for(context, class);
         for component in class.iter() {
             match component {
                 class::IdPart::Namespace(id)        => write!(&mut buf, "{}::", rust_id(id)?)?,
@@ -112,7 +126,8 @@ impl Struct {
     }
 
     pub(crate) fn sharded_path_for(context: &Context, class: class::Id) -> Result<PathBuf, Box<dyn Error>> {
-        let rename_to = context.config.rename_classes.get(class.as_str()).map(|name| name.as_str()).ok_or(());
+        let rename_to = context.config.rename_class(class.as_str());
+        let rename_to = rename_to.as_deref().ok_or(());
 
         let mut buf = String::new();
 
@@ -131,15 +146,49 @@ impl Struct {
         Ok(PathBuf::from(buf))
     }
 
-    pub(crate) fn new<'ctx>(context: &'ctx mut Context, java: jreflection::Class) -> Result<Self, Box<dyn Error>> {
+    pub(crate) fn new<'ctx>(context: &'ctx mut Context, java: jreflection::Class, attributes: ClassAttributes) -> Result<Self, Box<dyn Error>> {
         let rust = StructPaths::new(context, java.path.as_id())?;
 
         return Ok(Self {
             rust,
             java,
+            attributes,
         });
     }
 
+    /// The Rust types of this struct's superclass ("()" if none) and interfaces - including type arguments for
+    /// `generics = "phantom"`.  Features required by the struct definition are added to `required_features`.
+    pub(crate) fn rust_supertypes(&self, context: &Context, required_features: &mut BTreeSet<String>) -> (String, Vec<String>) {
+        let (super_path, interfaces) = context.resolve_supertypes(&self.java);
+
+        let scope = TypeScope::for_class(context, self.java.path.as_id());
+        let signature = context.known.get(self.java.path.as_str()).and_then(|known| known.signature.as_ref());
+        if !struct_type_params(context, self.java.path.as_id()).is_empty() {
+            object_type(context, required_features); // Type parameter defaults
+        }
+
+        let mut rust_supertype = |path: &class::IdBuf| -> String {
+            let generic = signature.and_then(|signature| std::iter::once(&signature.super_class).chain(signature.interfaces.iter()).find(|generic| generic.path == path.as_str()));
+            if let Some(generic) = generic {
+                if let Some(rust) = rust_type(context, &scope, &TypeSignature::Class(generic.clone()), required_features, &mut BTreeSet::new()) {
+                    return rust;
+                }
+            }
+            if let Ok(feature) = context.required_feature_for(path.as_id()) {
+                required_features.extend(feature);
+            }
+            context.java_to_rust_path(path.as_id()).unwrap()
+        };
+
+        let super_path = if let Some(super_path) = super_path.as_ref() {
+            rust_supertype(super_path)
+        } else {
+            "()".to_owned() // java.lang.Object, or a class whose superclasses are all classpath-only
+        };
+        let interfaces = interfaces.iter().map(&mut rust_supertype).collect();
+        (super_path, interfaces)
+    }
+
     pub(crate) fn write(&self, context: &Context, indent: &str, out: &mut impl io::Write) -> io::Result<()> {
         writeln!(out, "")?;
 
@@ -167,10 +216,17 @@ impl Struct {
             if self.java.deprecated { "#[deprecated] " } else { "" }
         );
 
-        let super_path = if let Some(super_path) = self.java.super_path.as_ref() {
-            context.java_to_rust_path(super_path.as_id()).unwrap()
+        let (super_path, interfaces) = self.rust_supertypes(context, &mut BTreeSet::new());
+
+        // generics = "phantom":  e.g. "generic interface List<E = crate::java::lang::Object>"
+        let type_params = struct_type_params(context, self.java.path.as_id());
+        let (macro_keyword, type_params_decl) = if type_params.is_empty() {
+            (keyword.to_owned(), String::new())
         } else {
-            "()".to_owned() // This might only happen for java.lang.Object
+            let object = object_type(context, &mut BTreeSet::new()).unwrap(); // struct_type_params checks java.lang.Object is available
+            let kind = if self.java.is_interface() { "interface" } else { "class" };
+            let decl = type_params.iter().map(|param| format!("{} = {}", param, &object)).collect::<Vec<_>>().join(", ");
+            (format!("generic {}", kind), format!("<{}>", decl))
         };
 
         if let Ok(required_feature) = Struct::feature_for(context, self.java.path.as_id()) {
@@ -186,22 +242,22 @@ impl Struct {
             writeln!(out, "{}    ///", indent)?;
             writeln!(out, "{}    /// Required feature: {:?}", indent, required_feature)?;
         }
-        write!(out, "{}    {}{} {} {} ({:?}) extends {}", indent, attributes, visibility, keyword, &self.rust.struct_name, self.java.path.as_str(), super_path)?;
+        write!(out, "{}    {}{} {} {}{} ({:?}) extends {}", indent, attributes, visibility, macro_keyword, &self.rust.struct_name, type_params_decl, self.java.path.as_str(), super_path)?;
         let mut implements = false;
-        for interface in &self.java.interfaces {
+        for interface in &interfaces {
             write!(out, ", ")?;
             if !implements {
                 write!(out, "implements ")?;
                 implements = true;
             }
-            write!(out, "{}", &context.java_to_rust_path(interface.as_id()).unwrap())?;
+            write!(out, "{}", interface)?;
         }
         writeln!(out, " {{")?;
 
         let mut id_repeats = HashMap::new();
 
-        let mut methods : Vec<Method> = self.java.methods.iter().map(|m| Method::new(context, &self.java, m)).collect();
-        let mut fields  : Vec<Field > = self.java.fields.iter().map(|f| Field::new(context, &self.java, f)).collect();
+        let mut methods : Vec<Method> = self.java.methods.iter().map(|m| Method::new(context, &self.java, &self.attributes, m)).collect();
+        let mut fields  : Vec<Field > = self.java.fields.iter().map(|f| Field::new(context, &self.java, &self.attributes, f)).collect();
 
         for method in &methods {
             if !method.java.is_public() { continue; } // Skip private/protected methods
@@ -222,6 +278,9 @@ impl Struct {
             }
         }
 
+        // Generic structs put statics in a separate non-generic impl, so they can be called without picking type arguments
+        let mut static_out = Vec::new();
+
         for method in &mut methods {
             if let Some(name) = method.rust_name() {
                 let repeats = *id_repeats.get(name).unwrap_or(&0);
@@ -231,15 +290,30 @@ impl Struct {
                 }
             }
 
-            method.emit(context, indent, out)?;
+            if !type_params.is_empty() && method.java.is_static() {
+                method.emit(context, indent, &mut static_out)?;
+            } else {
+                method.emit(context, indent, out)?;
+            }
         }
 
         for field in &mut fields {
-            field.emit(context, indent, out)?;
+            if !type_params.is_empty() && field.java.is_static() {
+                field.emit(context, indent, &mut static_out)?;
+            } else {
+                field.emit(context, indent, out)?;
+            }
         }
 
+        if !type_params.is_empty() {
+            writeln!(out, "{}    }} static {{", indent)?;
+            out.write_all(&static_out[..])?;
+        }
         writeln!(out, "{}    }}", indent)?;
         writeln!(out, "{}}}", indent)?;
+
+        EnumMirror::new(self).emit(context, indent, out)?;
+        Natives::new(context, self).emit(context, indent, out)?;
         Ok(())
     }
 }
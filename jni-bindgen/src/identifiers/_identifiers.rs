@@ -4,9 +4,11 @@
 use std::iter::*;
 
 mod field_mangling_style;
+mod jni_native_symbol;
 mod method_mangling_style;
 mod rust_identifier;
 
 pub use field_mangling_style::*;
+pub use jni_native_symbol::*;
 pub use method_mangling_style::*;
 pub use rust_identifier::*;
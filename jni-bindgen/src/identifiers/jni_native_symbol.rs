@@ -0,0 +1,57 @@
+use std::fmt::Write;
+
+
+
+/// Generates the symbol name the JVM looks for when linking a Java `native` method, per the JNI spec's
+/// [Resolving Native Method Names](https://docs.oracle.com/javase/8/docs/technotes/guides/jni/spec/design.html#resolving_native_method_names).
+///
+/// `class` is the JNI path of the class (e.g. "com/example/Foo$Inner".)  If `overloaded_descriptor` is provided (e.g.
+/// "(ILjava/lang/String;)V"), the "long" name is generated, which includes the argument signature, for disambiguating
+/// overloaded native methods.
+///
+/// # Examples:
+///
+/// | Java                                       | Symbol                                           |
+/// | ------------------------------------------ | ------------------------------------------------ |
+/// | com/example/Foo.bar                        | Java_com_example_Foo_bar                         |
+/// | com/example_app/Foo$Inner.bar_baz          | Java_com_example_1app_Foo_00024Inner_bar_1baz    |
+/// | com/example/Foo.bar(I[Ljava/lang/String;)V | Java_com_example_Foo_bar__I_3Ljava_lang_String_2 |
+pub fn jni_native_symbol(class: &str, method: &str, overloaded_descriptor: Option<&str>) -> String {
+    let mut buffer = String::from("Java_");
+    jni_mangle_into(&mut buffer, class);
+    buffer.push('_');
+    jni_mangle_into(&mut buffer, method);
+    if let Some(descriptor) = overloaded_descriptor {
+        let args = descriptor.trim_start_matches('(');
+        let args = &args[..args.find(')').unwrap_or(args.len())];
+        buffer.push_str("__");
+        jni_mangle_into(&mut buffer, args);
+    }
+    buffer
+}
+
+fn jni_mangle_into(buffer: &mut String, s: &str) {
+    for ch in s.chars() {
+        match ch {
+            '/'                 => buffer.push('_'),
+            '_'                 => buffer.push_str("_1"),
+            ';'                 => buffer.push_str("_2"),
+            '['                 => buffer.push_str("_3"),
+            'a'..='z' | 'A'..='Z' | '0'..='9' => buffer.push(ch),
+            _ => {
+                let mut utf16 = [0u16; 2];
+                for unit in ch.encode_utf16(&mut utf16) {
+                    write!(buffer, "_0{:04x}", unit).unwrap();
+                }
+            },
+        }
+    }
+}
+
+#[test] fn jni_native_symbol_test() {
+    assert_eq!(jni_native_symbol("com/example/Foo",             "bar",      None),                          "Java_com_example_Foo_bar");
+    assert_eq!(jni_native_symbol("com/example_app/Foo$Inner",   "bar_baz",  None),                          "Java_com_example_1app_Foo_00024Inner_bar_1baz");
+    assert_eq!(jni_native_symbol("com/example/Foo",             "bar",      Some("()V")),                   "Java_com_example_Foo_bar__");
+    assert_eq!(jni_native_symbol("com/example/Foo",             "bar",      Some("(I[Ljava/lang/String;)V")), "Java_com_example_Foo_bar__I_3Ljava_lang_String_2");
+    assert_eq!(jni_native_symbol("com/example/Füß",             "\u{1F600}",None),                          "Java_com_example_F_000fc_000df__0d83d_0de00");
+}
@@ -1,6 +1,7 @@
 use jreflection::{io_data_err, io_data_error};
 
 #[path = "android/_android.rs"]         mod android;
+#[path = "class_file/_class_file.rs"]   mod class_file;
 #[path = "config/_config.rs"]           mod config;
 #[path = "emit_rust/_emit_rust.rs"]     mod emit_rust;
 #[path = "identifiers/_identifiers.rs"] mod identifiers;
@@ -19,8 +20,8 @@ mod entry {
 
     use clap::load_yaml;
 
-    use std::fs::{File};
-    use std::io::{self, BufRead, BufReader, BufWriter, Write};
+    use std::fs::{self, File};
+    use std::io::{self, BufRead, BufReader, Write};
     use std::path::*;
     use std::process::exit;
 
@@ -49,8 +50,12 @@ mod entry {
         let subcommand = matches.subcommand_name().unwrap_or("generate");
 
         match subcommand {
-            "generate" => {
+            "generate" | "verify" => {
+                let verify = subcommand == "verify";
+                let run_once = |config_file| if verify { run::verify(config_file) } else { run(config_file) };
+
                 let mut config_file = config::toml::File::from_directory(directory).unwrap();
+                let mut differences = Vec::new();
 
                 let result = if let Some(api_levels) = android_api_levels.as_ref() {
                     let mut result = None;
@@ -66,22 +71,54 @@ mod entry {
                         config_file.file.input.files.clear();
                         config_file.file.input.files.push(PathBuf::from(sdk_android_jar));
                         config_file.file.output.path = PathBuf::from(format!("src/generated/api-level-{}.rs", api_level));
-                        result = run(config_file.clone()).ok();
+                        result = run_once(config_file.clone()).ok();
+                        if let Some(result) = result.as_mut() {
+                            differences.append(&mut result.differences);
+                        }
                     }
                     result.unwrap()
                 } else {
-                    run(config_file).unwrap()
+                    let mut result = run_once(config_file).unwrap();
+                    differences.append(&mut result.differences);
+                    result
                 };
 
-                if let Err(e) = generate_toml(directory, android_api_levels.as_ref(), &result) {
-                    eprintln!("ERROR:  Failed to regenerate Cargo.toml:\n    {:?}", e);
+                let toml = match generate_toml(directory, android_api_levels.as_ref(), &result) {
+                    Ok(toml) => toml,
+                    Err(e) => {
+                        eprintln!("ERROR:  Failed to regenerate Cargo.toml:\n    {:?}", e);
+                        exit(1);
+                    },
+                };
+
+                let toml_path = directory.join("Cargo.toml");
+                let toml_result = if verify {
+                    util::FileDifference::find(&toml_path, &toml[..]).map(|difference| differences.extend(difference))
+                } else {
+                    fs::write(&toml_path, &toml[..])
+                };
+
+                if let Err(e) = toml_result {
+                    eprintln!("ERROR:  Failed to {} Cargo.toml:\n    {:?}", if verify { "verify" } else { "regenerate" }, e);
+                    exit(1);
+                }
+
+                if !differences.is_empty() {
+                    eprintln!("ERROR:  {} generated file(s) don't match the existing files on disk:", differences.len());
+                    for file in differences.iter() {
+                        match file.difference.as_ref() {
+                            None => eprintln!("    MISSING:  {}", file.path.display()),
+                            Some(difference) => {
+                                eprintln!("    MODIFIED: {}:{}", file.path.display(), difference.line_no);
+                                eprintln!("        on disk:    {:?}", difference.original);
+                                eprintln!("        generated:  {:?}", difference.rewrite);
+                            },
+                        }
+                    }
+                    eprintln!("Run `jni-bindgen generate` to regenerate them.");
+                    debugger::break_if_attached();
                     exit(1);
                 }
-            },
-            "verify" => {
-                eprintln!("verify not yet implemented");
-                debugger::break_if_attached();
-                exit(1);
             },
             unknown => {
                 eprintln!("Unexpected subcommand: {}", unknown);
@@ -91,11 +128,11 @@ mod entry {
         }
     }
 
-    fn generate_toml(directory: &Path, api_levels: Option<&android::ApiLevelRange>, result: &RunResult) -> io::Result<()> {
+    fn generate_toml(directory: &Path, api_levels: Option<&android::ApiLevelRange>, result: &RunResult) -> io::Result<Vec<u8>> {
         // XXX: Check that Cargo.toml is marked as generated
 
         let template    = BufReader::new(File::open(directory.join("Cargo.toml.template"))?);
-        let mut out     = BufWriter::new(File::create(directory.join("Cargo.toml"))?);
+        let mut out     = Vec::new();
 
         writeln!(out, "# WARNING:  This file was autogenerated by jni-bindgen.  Any changes to this file may be lost!!!")?;
         writeln!(out, "")?;
@@ -122,6 +159,7 @@ mod entry {
                 "# PLACEHOLDER:FEATURES:sharded-api" => {
                     writeln!(out, "{}:BEGIN", line)?;
                     for (feature, dependencies) in result.features.iter() {
+                        if feature == "all" { continue; }
                         write!(out, "{:?} = [", feature)?;
                         for (idx, dependency) in dependencies.iter().enumerate() {
                             if idx != 0 {
@@ -134,7 +172,15 @@ mod entry {
 
                     // Wildcard feature "*".  While it's tempting to make this depend on all other features, this
                     // causes problems on windows where we run into command line length limits invoking rustc.
-                    writeln!(out, "\"all\" = []")?;
+                    // It does however enable "all" of any [[extern]] crates we reference.
+                    write!(out, "\"all\" = [")?;
+                    for (idx, dependency) in result.features.get("all").into_iter().flatten().enumerate() {
+                        if idx != 0 {
+                            write!(out, ", ")?;
+                        }
+                        write!(out, "{:?}", dependency)?;
+                    }
+                    writeln!(out, "]")?;
                     writeln!(out, "{}:END", line)?;
                 },
                 "# PLACEHOLDER:FEATURES:docs.rs" => {
@@ -155,6 +201,6 @@ mod entry {
             }
         }
 
-        Ok(())
+        Ok(out)
     }
 }
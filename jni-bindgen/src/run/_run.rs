@@ -5,4 +5,5 @@
 mod run;
 
 pub use run::run;
+pub use run::verify;
 pub use run::RunResult;
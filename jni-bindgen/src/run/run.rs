@@ -1,3 +1,4 @@
+use crate::class_file::ClassAttributes;
 use crate::config::runtime::*;
 use crate::emit_rust;
 use crate::util;
@@ -12,26 +13,43 @@ use std::io;
 use std::path::*;
 use std::result::Result;
 
-/// The result of calling [run].
+/// The result of calling [run] or [verify].
 /// 
 /// [run]:      fn.run.html
+/// [verify]:   fn.verify.html
 pub struct RunResult {
     /// What features this crate assumes exist, and the features that feature is expected to depend on.
     pub features: BTreeMap<String, BTreeSet<String>>,
+
+    /// Generated files that didn't match what was on disk.  Always empty when generating.
+    pub differences: Vec<util::FileDifference>,
 }
 
 /// The core function of this library: Generate Rust code to access Java APIs.
 pub fn run(config: impl Into<Config>) -> Result<RunResult, Box<dyn Error>> {
-    let config : Config = config.into();
+    run_impl(config.into(), false)
+}
+
+/// Generate Rust code to access Java APIs in memory, and compare it against the existing files without writing them.
+pub fn verify(config: impl Into<Config>) -> Result<RunResult, Box<dyn Error>> {
+    run_impl(config.into(), true)
+}
+
+fn run_impl(config: Config, verify: bool) -> Result<RunResult, Box<dyn Error>> {
     if config.logging_verbose {
     }
     println!("output: {}", config.output_path.display());
 
     lazy_static! { static ref FILE_SET : util::ConcurrentDedupeFileSet = util::ConcurrentDedupeFileSet::new(); }
-    let mut context = emit_rust::Context::new(&*FILE_SET, &config);
+    let mut context = emit_rust::Context::new(&*FILE_SET, &config, verify);
     for file in config.input_files.iter() {
-        gather_file(&mut context, file)?;
+        gather_file(&mut context, file, false)?;
+    }
+    for file in config.input_classpath.iter() {
+        gather_file(&mut context, file, true)?;
     }
+    context.resolve_includes();
+    context.resolve_features();
 
     {
         let mut out = Vec::new();
@@ -40,12 +58,22 @@ pub fn run(config: impl Into<Config>) -> Result<RunResult, Box<dyn Error>> {
         util::write_generated(&context, &config.output_path, &out[..])?;
     }
 
+    let differences = std::mem::replace(&mut *context.differences.lock().unwrap(), Vec::new());
+    let mut features = context.features.clone();
+    for (feature, extern_features) in context.extern_features.lock().unwrap().iter() {
+        features.entry(feature.clone()).or_insert(BTreeSet::new()).extend(extern_features.iter().cloned());
+    }
     Ok(RunResult{
-        features: context.features.clone(),
+        features,
+        differences,
     })
 }
 
-fn gather_file(context: &mut emit_rust::Context, path: &Path) -> Result<(), Box<dyn Error>> {
+fn gather_file(context: &mut emit_rust::Context, path: &Path, classpath: bool) -> Result<(), Box<dyn Error>> {
+    if path.is_dir() {
+        return gather_directory(context, path, classpath);
+    }
+
     context.progress.lock().unwrap().update(format!("reading {}...", path.display()).as_str());
 
     let ext = if let Some(ext) = path.extension() {
@@ -56,25 +84,212 @@ fn gather_file(context: &mut emit_rust::Context, path: &Path) -> Result<(), Box<
 
     match ext.to_string_lossy().to_ascii_lowercase().as_str() {
         "class" => {
-            let mut file = io::BufReader::new(File::open(path)?);
-            let class = Class::read(&mut file)?;
-            context.add_struct(class)?;
+            add_class(context, &mut File::open(path)?, classpath)?;
+        },
+        "jar" | "jmod" => { // .jmod s are .jar s with a 4 byte "JM\x01\x00" header, which zip skips over like any other prefix
+            gather_jar(context, io::BufReader::new(File::open(path)?), classpath)?;
         },
-        "jar" => {
-            let mut jar = zip::ZipArchive::new(io::BufReader::new(File::open(path)?))?;
-            let n = jar.len();
-
-            for i in 0..n {
-                let mut file = jar.by_index(i)?;
-                if !file.name().ends_with(".class") { continue; }
-                context.progress.lock().unwrap().update(format!("  reading {:3}/{}: {}...", i, n, file.name()).as_str());
-                let class = Class::read(&mut file)?;
-                context.add_struct(class)?;
+        "aar" => { // Android libraries: classes.jar + libs/*.jar, alongside resources etc. we don't care about
+            let mut aar = zip::ZipArchive::new(io::BufReader::new(File::open(path)?))?;
+            let mut jars = (0..aar.len()).filter_map(|i| {
+                let name = aar.by_index(i).ok()?.name().to_owned();
+                let is_jar = name == "classes.jar" || (name.starts_with("libs/") && name.ends_with(".jar"));
+                if is_jar { Some(name) } else { None }
+            }).collect::<Vec<_>>();
+            jars.sort();
+
+            for name in jars {
+                context.progress.lock().unwrap().update(format!("  reading {}...", name).as_str());
+                let mut jar = Vec::new();
+                io::copy(&mut aar.by_name(&name)?, &mut jar)?;
+                gather_jar(context, io::Cursor::new(jar), classpath)?;
             }
         },
         unknown => {
-            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("Input files must have a '.class' or '.jar' extension, not a '.{}' extension", unknown)))?;
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("Input files must be a directory, or have a '.class', '.jar', '.jmod', or '.aar' extension, not a '.{}' extension", unknown)))?;
         }
     }
     Ok(())
 }
+
+fn gather_jar(context: &mut emit_rust::Context, reader: impl io::Read + io::Seek, classpath: bool) -> Result<(), Box<dyn Error>> {
+    let mut jar = zip::ZipArchive::new(reader)?;
+    let n = jar.len();
+
+    for i in 0..n {
+        let mut file = jar.by_index(i)?;
+        if !is_class_file_name(file.name()) { continue; }
+        context.progress.lock().unwrap().update(format!("  reading {:3}/{}: {}...", i, n, file.name()).as_str());
+        add_class(context, &mut file, classpath)?;
+    }
+    Ok(())
+}
+
+/// Directories are either a JDK image (read `jmods/*.jmod`), or a classpath / exploded module image (read `**/*.class`).
+fn gather_directory(context: &mut emit_rust::Context, path: &Path, classpath: bool) -> Result<(), Box<dyn Error>> {
+    let jmods = path.join("jmods");
+    if jmods.is_dir() && path.join("release").is_file() {
+        for jmod in sorted_dir_entries(&jmods)? {
+            if jmod.extension().map_or(false, |ext| ext.eq_ignore_ascii_case("jmod")) {
+                gather_file(context, &jmod, classpath)?;
+            }
+        }
+        return Ok(());
+    }
+
+    for entry in sorted_dir_entries(path)? {
+        if entry.is_dir() {
+            gather_directory(context, &entry, classpath)?;
+        } else if entry.file_name().map_or(false, |name| is_class_file_name(&name.to_string_lossy())) {
+            gather_file(context, &entry, classpath)?;
+        }
+    }
+    Ok(())
+}
+
+fn add_class(context: &mut emit_rust::Context, reader: &mut impl io::Read, classpath: bool) -> Result<(), Box<dyn Error>> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    let class = Class::read(&mut &bytes[..])?;
+    if is_anonymous_or_local_class(class.path.as_str()) { return Ok(()); }
+    let attributes = ClassAttributes::read(&bytes[..]).unwrap_or_else(|err| {
+        // Only generic signatures etc. - we can still bind the class erased without them.
+        context.progress.lock().unwrap().force_update(format!("WARNING:  {}: unable to read class attributes, binding without them: {}", class.path.as_str(), err).as_str());
+        ClassAttributes::default()
+    });
+    for warning in attributes.warnings.iter() {
+        context.progress.lock().unwrap().force_update(format!("WARNING:  {}: {}", class.path.as_str(), warning).as_str());
+    }
+
+    if classpath {
+        context.add_classpath_class(class, attributes);
+        Ok(())
+    } else {
+        context.add_struct(class, attributes)
+    }
+}
+
+fn sorted_dir_entries(path: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut entries = read_dir(path)?.map(|entry| entry.map(|entry| entry.path())).collect::<io::Result<Vec<_>>>()?;
+    entries.sort(); // Deterministic output regardless of filesystem enumeration order
+    Ok(entries)
+}
+
+/// Anonymous (`Foo$1`) and local (`Foo$1Bar`) classes can't be named from outside their enclosing method, and have no
+/// Rust identifier anyways.
+fn is_anonymous_or_local_class(class: &str) -> bool {
+    let leaf = class.rsplit('/').next().unwrap_or(class);
+    leaf.split('$').skip(1).any(|part| part.starts_with(|ch: char| ch.is_ascii_digit()))
+}
+
+/// `module-info.class` describes a module, not a class we can bind.
+fn is_class_file_name(name: &str) -> bool {
+    name.ends_with(".class") && !name.ends_with("module-info.class")
+}
+
+#[test] fn gather_directory_and_jmod_skip_anonymous_classes() {
+    use crate::util::test_class_file::*;
+    use std::io::Write;
+
+    let dir = temp_dir("gather");
+    let classes = dir.join("classes");
+    write_class(&classes, "p/Foo",          &class_file(ACC_PUBLIC | ACC_SUPER, "p/Foo",        Some("java/lang/Object"), None, &[])).unwrap();
+    write_class(&classes, "p/Foo$1",        &class_file(ACC_SUPER,              "p/Foo$1",      Some("java/lang/Object"), None, &[])).unwrap();
+    write_class(&classes, "p/Foo$1Local",   &class_file(ACC_SUPER,              "p/Foo$1Local", Some("java/lang/Object"), None, &[])).unwrap();
+
+    // .jmod s are zips with a "JM\x01\x00" header
+    let mut zip = zip::ZipWriter::new(io::Cursor::new(Vec::new()));
+    for (class, access_flags) in &[("q/Bar", ACC_PUBLIC | ACC_SUPER), ("q/Bar$2", ACC_SUPER), ("q/Bar$Inner", ACC_PUBLIC | ACC_SUPER)] {
+        zip.start_file(format!("classes/{}.class", class), zip::write::FileOptions::default()).unwrap();
+        zip.write_all(&class_file(*access_flags, class, Some("java/lang/Object"), None, &[])).unwrap();
+    }
+    zip.start_file("classes/module-info.class", zip::write::FileOptions::default()).unwrap();
+    let mut jmod = b"JM\x01\x00".to_vec();
+    jmod.extend(zip.finish().unwrap().into_inner());
+    write(dir.join("test.jmod"), jmod).unwrap();
+
+    let generated = generate(&dir, r#"
+        [input]
+        files = ["classes", "test.jmod"]
+
+        [output]
+        path = "src/generated.rs"
+    "#);
+    for class in &["class Foo (", "class Bar (", "class Bar_Inner ("] {
+        assert!(generated.contains(class), "{:?} not generated:\n{}", class, generated);
+    }
+    for class in &["Foo_1", "Bar_2"] {
+        assert!(!generated.contains(class), "{:?} generated:\n{}", class, generated);
+    }
+}
+
+#[test] fn gather_aar_classes_jar_and_libs() {
+    use crate::util::test_class_file::*;
+    use std::io::Write;
+
+    fn zip(files: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let mut zip = zip::ZipWriter::new(io::Cursor::new(Vec::new()));
+        for (name, bytes) in files {
+            zip.start_file(*name, zip::write::FileOptions::default()).unwrap();
+            zip.write_all(bytes).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+    let class_entry = |class: &str| (format!("{}.class", class), class_file(ACC_PUBLIC | ACC_SUPER, class, Some("java/lang/Object"), None, &[]));
+    let jar = |class: &str| { let (name, bytes) = class_entry(class); zip(&[(name.as_str(), bytes)]) };
+
+    let dir = temp_dir("aar");
+    write(dir.join("test.aar"), zip(&[
+        ("classes.jar",         jar("p/Main")),
+        ("libs/dependency.jar", jar("p/Lib")),
+        ("libs/notes.txt",      b"not a jar".to_vec()),
+        ("assets/other.jar",    jar("p/Asset")),
+    ])).unwrap();
+
+    let generated = generate(&dir, r#"
+        [input]
+        files = ["classes", "test.aar"]
+
+        [output]
+        path = "src/generated.rs"
+    "#);
+    assert!(generated.contains("class Main ("), "{}", generated);
+    assert!(generated.contains("class Lib ("), "{}", generated);
+    assert!(!generated.contains("Asset"), "{}", generated);
+}
+
+#[test] fn extern_features_forwarded_to_feature_crate() {
+    use crate::util::test_class_file::*;
+
+    let dir = temp_dir("extern");
+    let classes = dir.join("classes");
+    write_class(&classes, "p/User", &class_file(ACC_PUBLIC | ACC_SUPER, "p/User", Some("java/lang/Object"), None, &[
+        (ACC_PUBLIC, "gated",   "Lext/Gated;",   None),
+        (ACC_PUBLIC, "ungated", "Lfree/Ungated;", None),
+    ])).unwrap();
+
+    let (generated, result) = generate_with_result(&dir, r#"
+        [input]
+        files = ["classes"]
+
+        [output]
+        path = "src/generated.rs"
+
+        [[extern]]
+        jni_prefix      = "ext/"
+        rust_prefix     = "ext_crate::"
+        feature_crate   = "ext-crate"
+
+        [[extern]]
+        jni_prefix      = "free/"
+        rust_prefix     = "free_crate::"
+    "#);
+    assert!(generated.contains("ext_crate::ext::Gated"),    "{}", generated);
+    assert!(generated.contains("free_crate::free::Ungated"), "{}", generated);
+    assert!(!generated.contains("class Gated ("),           "{}", generated);
+
+    let forwarded = |feature: &str| result.features.get(feature).into_iter().flatten().cloned().collect::<Vec<_>>();
+    assert_eq!(forwarded("ext-Gated"),  ["ext-crate/ext-Gated"]);
+    assert!(forwarded("all").contains(&"ext-crate/all".to_owned()), "{:?}", result.features);
+    assert!(!result.features.keys().any(|feature| feature.contains("Ungated")), "{:?}", result.features);
+}
@@ -4,8 +4,9 @@ mod dedupe_file_set;
 mod difference;
 mod generated_file;
 mod progress;
+#[cfg(test)] pub mod test_class_file;
 
 pub use dedupe_file_set::{ConcurrentDedupeFileSet, DedupeFileSet};
-pub use difference::Difference;
+pub use difference::{Difference, FileDifference};
 pub use generated_file::write_generated;
 pub use progress::Progress;
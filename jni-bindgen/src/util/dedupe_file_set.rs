@@ -31,7 +31,7 @@ impl DedupeFileSet {
             },
             Entry::Vacant(entry) => {
                 let buffer = entry.key(); // buffer was moved
-                if path.exists() {
+                if !context.verify && path.exists() {
                     // XXX: Make it configurable if we trust the file size alone for "this file changed" checks.
                     let meta = path.metadata()?;
                     if meta.len() == buffer.len() as u64 {
@@ -1,4 +1,6 @@
+use std::fs::File;
 use std::io::{self, *};
+use std::path::{Path, PathBuf};
 
 pub struct Difference {
     pub line_no:    u32,
@@ -18,6 +20,8 @@ impl Difference {
         let mut line_no = 0;
         loop {
             line_no += 1;
+            original_line.clear();
+            rewrite_line.clear();
 
             let a = read_line_no_eol(original, &mut original_line)?;
             let b = read_line_no_eol(rewrite, &mut rewrite_line)?;
@@ -33,6 +37,43 @@ impl Difference {
     }
 }
 
+#[test] fn difference_find_test() {
+    let same = Difference::find(&mut Cursor::new("a\nb\r\nc"), &mut Cursor::new("a\r\nb\nc\n")).unwrap();
+    assert!(same.is_none());
+
+    let diff = Difference::find(&mut Cursor::new("a\nb\nc\n"), &mut Cursor::new("a\nB\nc\n")).unwrap().unwrap();
+    assert_eq!(diff.line_no,  2);
+    assert_eq!(diff.original, "b");
+    assert_eq!(diff.rewrite,  "B");
+
+    let diff = Difference::find(&mut Cursor::new("a\n"), &mut Cursor::new("a\nb\n")).unwrap().unwrap();
+    assert_eq!(diff.line_no,  2);
+    assert_eq!(diff.original, "");
+    assert_eq!(diff.rewrite,  "b");
+}
+
+/// A generated file that doesn't match what's currently on disk.
+pub struct FileDifference {
+    pub path:       PathBuf,
+    /// The first mismatched line, or `None` if the file doesn't exist on disk at all.
+    pub difference: Option<Difference>,
+}
+
+impl FileDifference {
+    /// Compare `contents` against the existing file at `path`, without modifying anything.
+    pub fn find(path: &Path, contents: &[u8]) -> io::Result<Option<FileDifference>> {
+        let difference = match File::open(path) {
+            Ok(file) => match Difference::find(&mut BufReader::new(file), &mut Cursor::new(contents))? {
+                None => return Ok(None),
+                Some(difference) => Some(difference),
+            },
+            Err(ref e) if e.kind() == ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+        Ok(Some(FileDifference { path: path.to_owned(), difference }))
+    }
+}
+
 fn read_line_no_eol(reader: &mut impl BufRead, buffer: &mut String) -> io::Result<usize> {
     let size = reader.read_line(buffer)?;
     while buffer.ends_with('\r') || buffer.ends_with('\n') {
@@ -1,4 +1,4 @@
-use crate::util::{Difference, *};
+use crate::util::{Difference, FileDifference, *};
 
 use std::fs::{self, *};
 use std::io::{self, BufRead, BufReader, Cursor, ErrorKind};
@@ -10,6 +10,10 @@ const MARKER_COMMENT : &'static str = "WARNING:  This file was autogenerated by
 
 pub fn write_generated(context: &emit_rust::Context, path: &impl AsRef<Path>, contents: &[u8]) -> io::Result<()> {
     let path = path.as_ref();
+    if context.verify {
+        return verify_generated(context, path, contents);
+    }
+
     let dir = path.parent().ok_or_else(|| io_data_error!("{:?} has no parent directory", path))?;
     let _ = create_dir_all(dir);
 
@@ -53,6 +57,20 @@ pub fn write_generated(context: &emit_rust::Context, path: &impl AsRef<Path>, co
 
 
 
+/// Like write_generated, but only records how `path` differs from `contents` instead of overwriting it.
+fn verify_generated(context: &emit_rust::Context, path: &Path, contents: &[u8]) -> io::Result<()> {
+    match FileDifference::find(path, contents)? {
+        None => {
+            context.progress.lock().unwrap().update(format!("verified: {}...", path.display()).as_str());
+        },
+        Some(difference) => {
+            context.progress.lock().unwrap().force_update(format!("{}: {}", if difference.difference.is_some() { "MODIFIED" } else { "MISSING" }, path.display()).as_str());
+            context.differences.lock().unwrap().push(difference);
+        },
+    }
+    Ok(())
+}
+
 fn read_line_no_eol(reader: &mut impl BufRead, buffer: &mut String) -> io::Result<usize> {
     let size = reader.read_line(buffer)?;
     while buffer.ends_with('\r') || buffer.ends_with('\n') {
@@ -0,0 +1,163 @@
+//! Minimal `.class` file writer for tests that need input classes without a JDK.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const ACC_PUBLIC    : u16 = 0x0001;
+pub const ACC_STATIC    : u16 = 0x0008;
+pub const ACC_FINAL     : u16 = 0x0010;
+pub const ACC_SUPER     : u16 = 0x0020;
+pub const ACC_INTERFACE : u16 = 0x0200;
+pub const ACC_ABSTRACT  : u16 = 0x0400;
+pub const ACC_ENUM      : u16 = 0x4000;
+
+/// A field:  (access_flags, name, descriptor, generic signature)
+pub type TestField<'a> = (u16, &'a str, &'a str, Option<&'a str>);
+
+/// A field or method with raw attributes:  (access_flags, name, descriptor, [(attribute name, attribute bytes)])
+pub type TestMember<'a> = (u16, &'a str, &'a str, Vec<(&'a str, Vec<u8>)>);
+
+/// The bytes of a class file declaring `class`, extending `super_class`, with `fields` and no methods.
+pub fn class_file(access_flags: u16, class: &str, super_class: Option<&str>, signature: Option<&str>, fields: &[TestField]) -> Vec<u8> {
+    let mut constants = Constants::default();
+    let fields = fields.iter().map(|&(access_flags, name, descriptor, signature)| (access_flags, name, descriptor, signature_attribute(&mut constants, signature))).collect::<Vec<_>>();
+    let attributes = signature_attribute(&mut constants, signature);
+    class_file_with_members(constants, access_flags, class, super_class, &fields, &[], &attributes)
+}
+
+/// The bytes of a class file declaring `class`, extending `super_class`, with `fields`, `methods`, and `attributes` - whose
+/// raw bytes may refer to entries already added to `constants`.
+pub fn class_file_with_members(mut constants: Constants, access_flags: u16, class: &str, super_class: Option<&str>, fields: &[TestMember], methods: &[TestMember], attributes: &[(&str, Vec<u8>)]) -> Vec<u8> {
+    let this_index  = constants.class(class);
+    let super_index = super_class.map_or(0, |super_class| constants.class(super_class));
+
+    let mut body = Vec::new();
+    u2(&mut body, access_flags);
+    u2(&mut body, this_index);
+    u2(&mut body, super_index);
+    u2(&mut body, 0); // interfaces
+    for members in &[fields, methods] {
+        u2(&mut body, members.len() as u16);
+        for (access_flags, name, descriptor, attributes) in members.iter() {
+            u2(&mut body, *access_flags);
+            u2(&mut body, constants.utf8(name));
+            u2(&mut body, constants.utf8(descriptor));
+            write_attributes(&mut body, &mut constants, attributes);
+        }
+    }
+    write_attributes(&mut body, &mut constants, attributes);
+
+    let mut bytes = vec![0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 52]; // Java 8
+    u2(&mut bytes, constants.count + 1);
+    bytes.extend(constants.bytes);
+    bytes.extend(body);
+    bytes
+}
+
+/// Write `bytes` to `dir/class.class`, creating parent directories as needed.
+pub fn write_class(dir: &Path, class: &str, bytes: &[u8]) -> io::Result<PathBuf> {
+    let path = dir.join(format!("{}.class", class));
+    fs::create_dir_all(path.parent().unwrap())?;
+    fs::write(&path, bytes)?;
+    Ok(path)
+}
+
+/// Run jni-bindgen in `dir` as configured by `toml`, after adding a minimal `java/lang/Object` to `dir/classes`.  Returns
+/// every generated `.rs` file under `dir`, concatenated in path order, and removes `dir` again.
+pub fn generate(dir: &Path, toml: &str) -> String {
+    generate_with_result(dir, toml).0
+}
+
+/// [generate], also returning the [RunResult](crate::run::RunResult) - e.g. for the features generated code expects.
+pub fn generate_with_result(dir: &Path, toml: &str) -> (String, crate::run::RunResult) {
+    write_class(&dir.join("classes"), "java/lang/Object", &class_file(ACC_PUBLIC | ACC_SUPER, "java/lang/Object", None, None, &[])).unwrap();
+    fs::write(dir.join("jni-bindgen.toml"), toml).unwrap();
+    let result = crate::run::run(crate::config::toml::File::from_directory(dir).unwrap()).unwrap();
+
+    let mut generated = String::new();
+    read_rs_files(dir, &mut generated);
+    let _ = fs::remove_dir_all(dir);
+    (generated, result)
+}
+
+/// An empty, per-process directory under the system temp directory.
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("jni-bindgen-test-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn read_rs_files(dir: &Path, out: &mut String) {
+    let mut entries = fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().path()).collect::<Vec<_>>();
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            read_rs_files(&path, out);
+        } else if path.extension().map_or(false, |ext| ext == "rs") {
+            out.push_str(&fs::read_to_string(&path).unwrap());
+        }
+    }
+}
+
+/// A constant pool under construction.  Every method returns the index of the constant it adds.
+#[derive(Default)]
+pub struct Constants {
+    bytes:  Vec<u8>,
+    count:  u16,
+}
+
+impl Constants {
+    pub fn utf8(&mut self, s: &str) -> u16 {
+        self.modified_utf8(s.as_bytes())
+    }
+
+    /// A `CONSTANT_Utf8` of already encoded (or deliberately invalid) bytes.
+    pub fn modified_utf8(&mut self, bytes: &[u8]) -> u16 {
+        self.bytes.push(1);
+        u2(&mut self.bytes, bytes.len() as u16);
+        self.bytes.extend(bytes);
+        self.count += 1;
+        self.count
+    }
+
+    pub fn class(&mut self, name: &str) -> u16 {
+        let name = self.utf8(name);
+        self.bytes.push(7);
+        u2(&mut self.bytes, name);
+        self.count += 1;
+        self.count
+    }
+
+    /// A `CONSTANT_Long`, which takes up two constant pool slots.
+    pub fn long(&mut self, value: i64) -> u16 {
+        self.bytes.push(5);
+        self.bytes.extend(&value.to_be_bytes());
+        self.count += 2;
+        self.count - 1
+    }
+
+    /// A `CONSTANT_Double`, which takes up two constant pool slots.
+    pub fn double(&mut self, value: f64) -> u16 {
+        self.bytes.push(6);
+        self.bytes.extend(&value.to_bits().to_be_bytes());
+        self.count += 2;
+        self.count - 1
+    }
+}
+
+fn signature_attribute(constants: &mut Constants, signature: Option<&str>) -> Vec<(&'static str, Vec<u8>)> {
+    signature.map(|signature| ("Signature", constants.utf8(signature).to_be_bytes().to_vec())).into_iter().collect()
+}
+
+fn write_attributes(out: &mut Vec<u8>, constants: &mut Constants, attributes: &[(&str, Vec<u8>)]) {
+    u2(out, attributes.len() as u16);
+    for (name, bytes) in attributes {
+        u2(out, constants.utf8(name));
+        out.extend(&(bytes.len() as u32).to_be_bytes());
+        out.extend(bytes);
+    }
+}
+
+fn u2(out: &mut Vec<u8>, value: u16) { out.extend(&value.to_be_bytes()); }
@@ -30,6 +30,45 @@ macro_rules! __jni_bindgen {
         __jni_bindgen! { $($rest)* }
     };
 
+    (@deref_generic $params:tt $from:ident => (); $($rest:tt)*) => {
+        __jni_bindgen! { $($rest)* }
+    };
+
+    (@deref_generic ($($param:ident),+) $from:ident => $target:ty; $($rest:tt)*) => {
+        impl<$($param: $crate::AsValidJObjectAndEnv),+> $crate::std::ops::Deref for $from<$($param),+> {
+            type Target = $target;
+            fn deref(&self) -> &Self::Target {
+                unsafe { &*(self as *const Self as *const Self::Target) }
+            }
+        }
+        __jni_bindgen! { $($rest)* }
+    };
+
+    (@implements_generic ($($param:ident),+) $from:ident => $target:ty; $($rest:tt)*) => {
+        impl<$($param: $crate::AsValidJObjectAndEnv),+> $crate::std::convert::AsRef<$target> for $from<$($param),+> {
+            fn as_ref(&self) -> &$target {
+                unsafe { &*(self as *const Self as *const $target) }
+            }
+        }
+        __jni_bindgen! { $($rest)* }
+    };
+
+    // Generic classes/interfaces (codegen.generics = "phantom").  Instance members are implemented for any type arguments,
+    // statics only for the defaults - so they can be called without picking type arguments.
+    (@generic $params:tt $(#[$attr:meta])* ($($vis:tt)*) $name:ident <$($param:ident = $default:ty),+> ($jni_type:expr) extends $parent:ty $(, implements $($interface:ty),+)* { $($body:tt)* } static { $($static_body:tt)* } $($rest:tt)*) => {
+        $(#[$attr])* #[repr(transparent)] $($vis)* struct $name<$($param: $crate::AsValidJObjectAndEnv = $default),+>(pub(crate) $crate::ObjectAndEnv, pub(crate) $crate::std::marker::PhantomData<($($param,)+)>);
+        impl<$($param: $crate::AsValidJObjectAndEnv),+> $name<$($param),+> { $($body)* }
+        impl $name { $($static_body)* }
+        unsafe impl<$($param: $crate::AsValidJObjectAndEnv),+> $crate::AsValidJObjectAndEnv for $name<$($param),+> {}
+        unsafe impl<$($param: $crate::AsValidJObjectAndEnv),+> $crate::AsJValue for $name<$($param),+> { fn as_jvalue(&self) -> $crate::jni_sys::jvalue { $crate::jni_sys::jvalue { l: self.0.object } } }
+        unsafe impl<$($param: $crate::AsValidJObjectAndEnv),+> $crate::JniType for $name<$($param),+> { fn static_with_jni_type<__R>(callback: impl FnOnce(&str) -> __R) -> __R { callback($jni_type) } }
+        __jni_bindgen! {
+            $($(@implements_generic $params $name => $interface;)*)*
+            @deref_generic $params $name => $parent;
+            $($rest)*
+        }
+    };
+
 
 
     ($(#[$attr:meta])* private static class $name:ident ($jni_type:expr) extends $parent:ty $(, implements $($interface:ty),+)* { $($body:tt)* } $($rest:tt)*) => {
@@ -96,6 +135,15 @@ macro_rules! __jni_bindgen {
 
 
 
+    ($(#[$attr:meta])* private generic $kind:ident $name:ident <$($param:ident = $default:ty),+> ($jni_type:expr) extends $parent:ty $(, implements $($interface:ty),+)* { $($body:tt)* } static { $($static_body:tt)* } $($rest:tt)*) => {
+        __jni_bindgen! {
+            @generic ($($param),+) $(#[$attr])* () $name <$($param = $default),+> ($jni_type) extends $parent $(, implements $($interface),+)* { $($body)* } static { $($static_body)* }
+            $($rest)*
+        }
+    };
+
+
+
     ($(#[$attr:meta])* public static class $name:ident ($jni_type:expr) extends $parent:ty $(, implements $($interface:ty),+)* { $($body:tt)* } $($rest:tt)*) => {
         $(#[$attr])* #[repr(transparent)] pub struct $name;
         impl $name { $($body)* }
@@ -157,4 +205,11 @@ macro_rules! __jni_bindgen {
             $($rest)*
         }
     };
+
+    ($(#[$attr:meta])* public generic $kind:ident $name:ident <$($param:ident = $default:ty),+> ($jni_type:expr) extends $parent:ty $(, implements $($interface:ty),+)* { $($body:tt)* } static { $($static_body:tt)* } $($rest:tt)*) => {
+        __jni_bindgen! {
+            @generic ($($param),+) $(#[$attr])* (pub) $name <$($param = $default),+> ($jni_type) extends $parent $(, implements $($interface),+)* { $($body)* } static { $($static_body)* }
+            $($rest)*
+        }
+    };
 }
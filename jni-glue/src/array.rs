@@ -84,6 +84,192 @@ pub trait PrimitiveArray<T> where Self : Sized + AsValidJObjectAndEnv, T : Clone
     fn as_vec(&self) -> Vec<T> {
         self.get_region_as_vec(0..self.len())
     }
+
+    /// Uses env.Get{Type}ArrayElements to read the contents of the java array in place (if the JVM supports pinning
+    /// it), or as a copy.  Any copy is discarded (`JNI_ABORT`) when the guard is dropped.
+    ///
+    /// Like [get_region], Java code may concurrently modify the same memory.
+    ///
+    /// [get_region]:   #tymethod.get_region
+    fn elements(&self) -> ArrayElements<'_, T, Self> {
+        let (ptr, len, is_copy) = unsafe { self.get_elements() };
+        ArrayElements { array: self, ptr, len, is_copy, _phantom: PhantomData }
+    }
+
+    /// Uses env.Get{Type}ArrayElements to access the contents of the java array in place (if the JVM supports pinning
+    /// it), or as a copy that's written back by env.Release{Type}ArrayElements when the guard is dropped.
+    ///
+    /// **unsafe**:  When the JVM pins the array instead of copying it, the guard is a `&mut [T]` to the java array's own
+    /// memory:  nothing else - other guards, other references to the same array, or Java code on other threads - may
+    /// access the array until the guard is dropped.
+    unsafe fn elements_mut(&self) -> ArrayElementsMut<'_, T, Self> {
+        let (ptr, len, is_copy) = self.get_elements();
+        ArrayElementsMut { array: self, ptr, len, is_copy, _phantom: PhantomData }
+    }
+
+    #[doc(hidden)] unsafe fn get_elements_raw(&self, is_copy: &mut jboolean) -> *mut T;
+    #[doc(hidden)] unsafe fn release_elements_raw(&self, elements: *mut T, mode: jint);
+    #[doc(hidden)] fn as_ref_object(&self) -> &ObjectAndEnv;
+
+    #[doc(hidden)] unsafe fn get_elements(&self) -> (*mut T, usize, bool) {
+        let len = self.len();
+        let mut is_copy = JNI_FALSE;
+        let ptr = self.get_elements_raw(&mut is_copy);
+        assert!(!ptr.is_null()); // Only sane failure here is an OOM
+        (ptr, len, is_copy == JNI_TRUE)
+    }
+}
+
+/// The contents of a [PrimitiveArray], as returned by [elements].  Read only - any copy is discarded when dropped.
+///
+/// [PrimitiveArray]:   trait.PrimitiveArray.html
+/// [elements]:         trait.PrimitiveArray.html#method.elements
+pub struct ArrayElements<'a, T: Clone + Default, A: PrimitiveArray<T>> {
+    array:      &'a A,
+    ptr:        *mut T,
+    len:        usize,
+    is_copy:    bool,
+    _phantom:   PhantomData<T>,
+}
+
+impl<'a, T: Clone + Default, A: PrimitiveArray<T>> ArrayElements<'a, T, A> {
+    /// Are these elements a copy of the java array (as opposed to the java array's memory, pinned in place)?
+    pub fn is_copy(&self) -> bool { self.is_copy }
+}
+
+impl<'a, T: Clone + Default, A: PrimitiveArray<T>> Deref for ArrayElements<'a, T, A> {
+    type Target = [T];
+    fn deref(&self) -> &[T] { unsafe { std::slice::from_raw_parts(self.ptr, self.len) } }
+}
+
+impl<'a, T: Clone + Default, A: PrimitiveArray<T>> Drop for ArrayElements<'a, T, A> {
+    fn drop(&mut self) {
+        unsafe { self.array.release_elements_raw(self.ptr, JNI_ABORT) };
+    }
+}
+
+/// The contents of a [PrimitiveArray], as returned by [elements_mut].  Changes are written back to the java array
+/// when dropped (`mode = 0`), unless [abort]ed.
+///
+/// [PrimitiveArray]:   trait.PrimitiveArray.html
+/// [elements_mut]:     trait.PrimitiveArray.html#method.elements_mut
+/// [abort]:            #method.abort
+pub struct ArrayElementsMut<'a, T: Clone + Default, A: PrimitiveArray<T>> {
+    array:      &'a A,
+    ptr:        *mut T,
+    len:        usize,
+    is_copy:    bool,
+    _phantom:   PhantomData<T>,
+}
+
+impl<'a, T: Clone + Default, A: PrimitiveArray<T>> ArrayElementsMut<'a, T, A> {
+    /// Are these elements a copy of the java array (as opposed to the java array's memory, pinned in place)?
+    pub fn is_copy(&self) -> bool { self.is_copy }
+
+    /// Write changes back to the java array without releasing the elements (`JNI_COMMIT`.)  A no-op if not a copy.
+    pub fn commit(&mut self) {
+        unsafe { self.array.release_elements_raw(self.ptr, JNI_COMMIT) };
+    }
+
+    /// Release the elements, discarding any changes not yet [commit]ed (`JNI_ABORT`.)  Changes will already have been
+    /// made to the java array if this isn't a copy.
+    ///
+    /// [commit]:   #method.commit
+    pub fn abort(self) {
+        unsafe { self.array.release_elements_raw(self.ptr, JNI_ABORT) };
+        std::mem::forget(self);
+    }
+}
+
+impl<'a, T: Clone + Default, A: PrimitiveArray<T>> Deref for ArrayElementsMut<'a, T, A> {
+    type Target = [T];
+    fn deref(&self) -> &[T] { unsafe { std::slice::from_raw_parts(self.ptr, self.len) } }
+}
+
+impl<'a, T: Clone + Default, A: PrimitiveArray<T>> DerefMut for ArrayElementsMut<'a, T, A> {
+    fn deref_mut(&mut self) -> &mut [T] { unsafe { std::slice::from_raw_parts_mut(self.ptr, self.len) } }
+}
+
+impl<'a, T: Clone + Default, A: PrimitiveArray<T>> Drop for ArrayElementsMut<'a, T, A> {
+    fn drop(&mut self) {
+        unsafe { self.array.release_elements_raw(self.ptr, 0) };
+    }
+}
+
+impl<'env, A: AsValidJObjectAndEnv> Local<'env, A> {
+    /// Uses env.GetPrimitiveArrayCritical to access the contents of the java array in place, which is more likely to
+    /// avoid a copy than [elements_mut], but may pause the garbage collector until the guard is dropped.  Keep the
+    /// critical region short!  The guard exclusively borrows the array - whose methods make JNI calls - and can't be
+    /// sent to other threads.
+    ///
+    /// ```rust,no_run
+    /// # use jni_glue::*;
+    /// # fn example(env: &Env) {
+    /// let mut array = IntArray::new(env, 4);
+    /// {
+    ///     let mut elements = unsafe { array.critical(env) };
+    ///     elements.copy_from_slice(&[1, 2, 3, 4]);
+    /// } // released - array.len() etc. can be called again
+    /// assert_eq!(array.as_vec(), [1, 2, 3, 4]);
+    /// # }
+    /// ```
+    ///
+    /// **unsafe**:  No other JNI functions may be called through `env` (or anything else belonging to it) - and the
+    /// thread must not block on other threads that might - until the returned guard is dropped.  As with
+    /// [elements_mut], nothing else may access the array until then either.
+    ///
+    /// [elements_mut]: trait.PrimitiveArray.html#method.elements_mut
+    pub unsafe fn critical<'a, T: Clone + Default>(&'a mut self, env: &'a Env) -> ArrayCritical<'a, T, A> where A: PrimitiveArray<T> {
+        let len = self.len();
+        let oae = self.as_ref_object();
+        assert_eq!(oae.env as *mut JNIEnv, env.as_jni_env()); // Soundness check - the array *must* belong to env!
+        let jni_env = env.as_jni_env();
+        let ptr = (**jni_env).GetPrimitiveArrayCritical.unwrap()(jni_env, oae.object, null_mut()) as *mut T;
+        assert!(!ptr.is_null()); // Only sane failure here is an OOM
+        ArrayCritical { object: oae.object, env, ptr, len, _array: PhantomData }
+    }
+}
+
+/// The contents of a [PrimitiveArray], as returned by [critical].  Changes are written back to the java array when
+/// dropped (`mode = 0`), unless [abort]ed.
+///
+/// [PrimitiveArray]:   trait.PrimitiveArray.html
+/// [critical]:         struct.Local.html#method.critical
+/// [abort]:            #method.abort
+pub struct ArrayCritical<'a, T: Clone + Default, A: PrimitiveArray<T>> {
+    object:     jobject,
+    env:        &'a Env,
+    ptr:        *mut T, // N.B. also makes us !Send:  critical regions must be released on the thread that entered them
+    len:        usize,
+    _array:     PhantomData<&'a mut A>, // Exclusively borrowed:  no JNI calls through its methods until we're released
+}
+
+impl<'a, T: Clone + Default, A: PrimitiveArray<T>> ArrayCritical<'a, T, A> {
+    /// Release the critical region, discarding any changes if the elements were a copy (`JNI_ABORT`.)
+    pub fn abort(self) {
+        self.release(JNI_ABORT);
+        std::mem::forget(self);
+    }
+
+    fn release(&self, mode: jint) {
+        let env = self.env.as_jni_env();
+        unsafe { (**env).ReleasePrimitiveArrayCritical.unwrap()(env, self.object, self.ptr as *mut c_void, mode) };
+    }
+}
+
+impl<'a, T: Clone + Default, A: PrimitiveArray<T>> Deref for ArrayCritical<'a, T, A> {
+    type Target = [T];
+    fn deref(&self) -> &[T] { unsafe { std::slice::from_raw_parts(self.ptr, self.len) } }
+}
+
+impl<'a, T: Clone + Default, A: PrimitiveArray<T>> DerefMut for ArrayCritical<'a, T, A> {
+    fn deref_mut(&mut self) -> &mut [T] { unsafe { std::slice::from_raw_parts_mut(self.ptr, self.len) } }
+}
+
+impl<'a, T: Clone + Default, A: PrimitiveArray<T>> Drop for ArrayCritical<'a, T, A> {
+    fn drop(&mut self) {
+        self.release(0);
+    }
 }
 
 // I assume jboolean as used exclusively by JNI/JVM is compatible with bool.
@@ -103,7 +289,7 @@ pub trait PrimitiveArray<T> where Self : Sized + AsValidJObjectAndEnv, T : Clone
 }
 
 macro_rules! primitive_array {
-    (#[repr(transparent)] pub struct $name:ident = $type_str:expr, $type:ident { $new_array:ident $set_region:ident $get_region:ident } ) => {
+    (#[repr(transparent)] pub struct $name:ident = $type_str:expr, $type:ident { $new_array:ident $set_region:ident $get_region:ident $get_elements:ident $release_elements:ident } ) => {
         /// A [PrimitiveArray](trait.PrimitiveArray.html) implementation.
         #[repr(transparent)] pub struct $name(ObjectAndEnv);
 
@@ -166,18 +352,28 @@ macro_rules! primitive_array {
 
                 unsafe { (**self.0.env).$set_region.unwrap()(self.0.env as *mut _, self.0.object, start, elements_len, elements.as_ptr() as *const _) };
             }
+
+            unsafe fn get_elements_raw(&self, is_copy: &mut jboolean) -> *mut $type {
+                (**self.0.env).$get_elements.unwrap()(self.0.env as *mut _, self.0.object, is_copy) as *mut $type
+            }
+
+            unsafe fn release_elements_raw(&self, elements: *mut $type, mode: jint) {
+                (**self.0.env).$release_elements.unwrap()(self.0.env as *mut _, self.0.object, elements as *mut _, mode)
+            }
+
+            fn as_ref_object(&self) -> &ObjectAndEnv { &self.0 }
         }
     };
 }
 
-primitive_array! { #[repr(transparent)] pub struct BooleanArray = "[Z\0", bool    { NewBooleanArray SetBooleanArrayRegion GetBooleanArrayRegion } }
-primitive_array! { #[repr(transparent)] pub struct ByteArray    = "[B\0", jbyte   { NewByteArray    SetByteArrayRegion    GetByteArrayRegion    } }
-primitive_array! { #[repr(transparent)] pub struct CharArray    = "[C\0", jchar   { NewCharArray    SetCharArrayRegion    GetCharArrayRegion    } }
-primitive_array! { #[repr(transparent)] pub struct ShortArray   = "[S\0", jshort  { NewShortArray   SetShortArrayRegion   GetShortArrayRegion   } }
-primitive_array! { #[repr(transparent)] pub struct IntArray     = "[I\0", jint    { NewIntArray     SetIntArrayRegion     GetIntArrayRegion     } }
-primitive_array! { #[repr(transparent)] pub struct LongArray    = "[J\0", jlong   { NewLongArray    SetLongArrayRegion    GetLongArrayRegion    } }
-primitive_array! { #[repr(transparent)] pub struct FloatArray   = "[F\0", jfloat  { NewFloatArray   SetFloatArrayRegion   GetFloatArrayRegion   } }
-primitive_array! { #[repr(transparent)] pub struct DoubleArray  = "[D\0", jdouble { NewDoubleArray  SetDoubleArrayRegion  GetDoubleArrayRegion  } }
+primitive_array! { #[repr(transparent)] pub struct BooleanArray = "[Z\0", bool    { NewBooleanArray SetBooleanArrayRegion GetBooleanArrayRegion GetBooleanArrayElements ReleaseBooleanArrayElements } }
+primitive_array! { #[repr(transparent)] pub struct ByteArray    = "[B\0", jbyte   { NewByteArray    SetByteArrayRegion    GetByteArrayRegion    GetByteArrayElements    ReleaseByteArrayElements    } }
+primitive_array! { #[repr(transparent)] pub struct CharArray    = "[C\0", jchar   { NewCharArray    SetCharArrayRegion    GetCharArrayRegion    GetCharArrayElements    ReleaseCharArrayElements    } }
+primitive_array! { #[repr(transparent)] pub struct ShortArray   = "[S\0", jshort  { NewShortArray   SetShortArrayRegion   GetShortArrayRegion   GetShortArrayElements   ReleaseShortArrayElements   } }
+primitive_array! { #[repr(transparent)] pub struct IntArray     = "[I\0", jint    { NewIntArray     SetIntArrayRegion     GetIntArrayRegion     GetIntArrayElements     ReleaseIntArrayElements     } }
+primitive_array! { #[repr(transparent)] pub struct LongArray    = "[J\0", jlong   { NewLongArray    SetLongArrayRegion    GetLongArrayRegion    GetLongArrayElements    ReleaseLongArrayElements    } }
+primitive_array! { #[repr(transparent)] pub struct FloatArray   = "[F\0", jfloat  { NewFloatArray   SetFloatArrayRegion   GetFloatArrayRegion   GetFloatArrayElements   ReleaseFloatArrayElements   } }
+primitive_array! { #[repr(transparent)] pub struct DoubleArray  = "[D\0", jdouble { NewDoubleArray  SetDoubleArrayRegion  GetDoubleArrayRegion  GetDoubleArrayElements  ReleaseDoubleArrayElements  } }
 
 /// A Java Array of reference types (classes, interfaces, other arrays, etc.)
 /// 
@@ -192,7 +388,13 @@ unsafe impl<T: AsValidJObjectAndEnv, E: ThrowableType> AsValidJObjectAndEnv for
 
 unsafe impl<T: AsValidJObjectAndEnv, E: ThrowableType> JniType for ObjectArray<T, E> {
     fn static_with_jni_type<R>(callback: impl FnOnce(&str) -> R) -> R {
-        T::static_with_jni_type(|inner| callback(format!("[{}", inner).as_str()))
+        T::static_with_jni_type(|inner| {
+            if inner.starts_with('[') || inner.ends_with(";\0") {
+                callback(format!("[{}", inner).as_str()) // e.g. "[[I\0", "[Ljava/lang/String;\0"
+            } else {
+                callback(format!("[L{};\0", inner.trim_end_matches('\0')).as_str()) // e.g. generated classes' "java/lang/String"
+            }
+        })
     }
 }
 
@@ -204,12 +406,21 @@ unsafe impl<T: AsValidJObjectAndEnv, E: ThrowableType> AsJValue for ObjectArray<
 
 impl<T: AsValidJObjectAndEnv, E: ThrowableType> ObjectArray<T, E> {
     pub fn new<'env>(env: &'env Env, size: usize) -> Local<'env, Self> {
+        Self::new_impl(env, size, null_mut())
+    }
+
+    /// Create a new array with every element set to `initial` (via NewObjectArray's initialElement parameter.)
+    pub fn new_filled<'env, 'e>(env: &'env Env, size: usize, initial: impl Into<Option<&'e T>>) -> Local<'env, Self> where T: 'e {
+        let initial = initial.into().map(|v| unsafe { AsJValue::as_jvalue(v).l }).unwrap_or(null_mut());
+        Self::new_impl(env, size, initial)
+    }
+
+    fn new_impl<'env>(env: &'env Env, size: usize, fill: jobject) -> Local<'env, Self> {
         assert!(size <= std::i32::MAX as usize); // jsize == jint == i32
-        let class = Self::static_with_jni_type(|t| unsafe { env.require_class(t) });
+        let class = T::static_with_jni_type(|t| unsafe { env.require_class(&jni_type_class_name(t)) }); // NewObjectArray takes the element class
         let size = size as jsize;
         let env = env.as_jni_env();
         unsafe {
-            let fill = null_mut();
             let object = (**env).NewObjectArray.unwrap()(env, size, class, fill);
             let exception = (**env).ExceptionOccurred.unwrap()(env);
             assert!(exception.is_null()); // Only sane exception here is an OOM exception
@@ -240,6 +451,51 @@ impl<T: AsValidJObjectAndEnv, E: ThrowableType> ObjectArray<T, E> {
         array
     }
 
+    /// Like [from], but doesn't require an [ExactSizeIterator] - elements are buffered until the length is known.
+    ///
+    /// ```rust,no_run
+    /// # use jni_glue::*;
+    /// # fn example<S: AsValidJObjectAndEnv, E: ThrowableType>(env: &Env, strings: &[Option<Local<S>>]) {
+    /// let non_null = ObjectArray::<S, E>::from_iter(env, strings.iter().flatten().map(|s| &**s));
+    /// for s in &*non_null {
+    ///     assert!(s.is_some());
+    /// }
+    ///
+    /// let reversed = non_null.iter().rev().collect::<Vec<_>>();
+    /// assert_eq!(reversed.len(), non_null.len());
+    /// let reversed = ObjectArray::<S, E>::from_iter(env, reversed.iter().map(Option::as_deref));
+    /// # }
+    /// ```
+    ///
+    /// [from]:                 #method.from
+    /// [ExactSizeIterator]:    https://doc.rust-lang.org/std/iter/trait.ExactSizeIterator.html
+    pub fn from_iter<'env, 'e>(env: &'env Env, elements: impl IntoIterator<Item = impl Into<Option<&'e T>>>) -> Local<'env, Self> where T: 'e {
+        let elements = elements.into_iter().map(|element| element.into().map(|v| unsafe { AsJValue::as_jvalue(v).l }).unwrap_or(null_mut())).collect::<Vec<_>>();
+        Self::from_jobjects(env, &elements[..])
+    }
+
+    /// Create a new array from a slice of [Global]s.  Panics if any of them belong to a different VM than `env`.
+    ///
+    /// [Global]:   struct.Global.html
+    pub fn from_slice<'env>(env: &'env Env, elements: &[Global<T>]) -> Local<'env, Self> {
+        let gen_vm = env.get_gen_vm();
+        let elements = elements.iter().map(|element| {
+            assert_eq!(element.gen_vm, gen_vm); // Soundness check - env *must* belong to the same VM!
+            element.global
+        }).collect::<Vec<_>>();
+        Self::from_jobjects(env, &elements[..])
+    }
+
+    fn from_jobjects<'env>(env: &'env Env, elements: &[jobject]) -> Local<'env, Self> {
+        let array   = Self::new(env, elements.len());
+        let env     = array.0.env as *mut JNIEnv;
+        let this    = array.0.object;
+        for (index, &value) in elements.iter().enumerate() {
+            unsafe { (**env).SetObjectArrayElement.unwrap()(env, this, index as jsize, value) };
+        }
+        array
+    }
+
     pub fn len(&self) -> usize {
         unsafe { (**self.0.env).GetArrayLength.unwrap()(self.0.env as *mut _, self.0.object) as usize }
     }
@@ -303,4 +559,28 @@ impl<'env, T: AsValidJObjectAndEnv, E: ThrowableType> Iterator for ObjectArrayIt
             None
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.length - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'env, T: AsValidJObjectAndEnv, E: ThrowableType> DoubleEndedIterator for ObjectArrayIter<'env, T, E> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.index < self.length {
+            self.length -= 1;
+            Some(self.array.get(self.length).unwrap_or(None))
+        } else {
+            None
+        }
+    }
+}
+
+impl<'env, T: AsValidJObjectAndEnv, E: ThrowableType> ExactSizeIterator for ObjectArrayIter<'env, T, E> {}
+
+impl<'env, T: AsValidJObjectAndEnv, E: ThrowableType> IntoIterator for &'env ObjectArray<T, E> {
+    type Item       = Option<Local<'env, T>>;
+    type IntoIter   = ObjectArrayIter<'env, T, E>;
+    fn into_iter(self) -> Self::IntoIter { self.iter() }
 }
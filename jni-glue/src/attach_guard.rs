@@ -0,0 +1,121 @@
+use super::*;
+use std::cell::RefCell;
+
+
+
+/// How to attach a thread to a [VM] via [VM::attach_with].
+///
+/// ```rust,no_run
+/// # use jni_glue::*;
+/// # fn example(vm: &VM) {
+/// let guard = vm.attach_with(&AttachOptions::new().name("worker").daemon(true));
+/// let env = guard.env();
+/// // ...
+/// # }
+/// ```
+///
+/// [VM]:               struct.VM.html
+/// [VM::attach_with]:  struct.VM.html#method.attach_with
+#[derive(Clone, Debug, Default)]
+pub struct AttachOptions<'a> {
+    daemon: bool,
+    name:   Option<CString>,
+    group:  Option<&'a jobject>,
+}
+
+impl<'a> AttachOptions<'a> {
+    pub fn new() -> Self { Self::default() }
+
+    /// Attach as a daemon thread (AttachCurrentThreadAsDaemon), which won't keep the JVM from shutting down.
+    pub fn daemon(mut self, daemon: bool) -> Self { self.daemon = daemon; self }
+
+    /// The name of the `java.lang.Thread`.  Panics if `name` contains `'\0'`.
+    pub fn name(mut self, name: impl Into<String>) -> Self { self.name = Some(CString::new(name.into()).expect("thread name contained '\\0'")); self }
+
+    /// The `java.lang.ThreadGroup` to add the thread to.
+    pub fn group<Class: AsValidJObjectAndEnv>(mut self, group: &'a Global<Class>) -> Self { self.group = Some(&group.global); self }
+}
+
+/// Keeps the current thread attached to a [VM].  Detaches on drop - but only if the thread wasn't already attached.
+///
+/// Not `Send`:  attachment is per-thread.
+///
+/// [VM]:   struct.VM.html
+pub struct AttachGuard<'vm> {
+    vm:     &'vm VM,
+    env:    *mut JNIEnv,
+    detach: bool,
+}
+
+impl<'vm> AttachGuard<'vm> {
+    pub(crate) unsafe fn new(vm: &'vm VM, options: &AttachOptions) -> Self {
+        let java_vm = vm.as_java_vm() as *mut JavaVM;
+        let mut env = null_mut();
+        match (**java_vm).GetEnv.unwrap()(java_vm, &mut env, JNI_VERSION_1_2) {
+            JNI_OK => return Self { vm, env: env as *mut JNIEnv, detach: false },
+            JNI_EDETACHED => {},
+            JNI_EVERSION => panic!("GetEnv returned JNI_EVERSION"),
+            unexpected => panic!("GetEnv returned unknown error: {}", unexpected),
+        }
+
+        let mut args = JavaVMAttachArgs {
+            version:    JNI_VERSION_1_2,
+            name:       options.name.as_ref().map_or(null_mut(), |name| name.as_ptr() as *mut c_char),
+            group:      options.group.map_or(null_mut(), |group| *group),
+        };
+        let args = &mut args as *mut JavaVMAttachArgs as *mut c_void;
+        let err = if options.daemon {
+            (**java_vm).AttachCurrentThreadAsDaemon.unwrap()(java_vm, &mut env, args)
+        } else {
+            (**java_vm).AttachCurrentThread.unwrap()(java_vm, &mut env, args)
+        };
+        match err {
+            JNI_OK => Self { vm, env: env as *mut JNIEnv, detach: true },
+            unexpected => panic!("AttachCurrentThread returned unknown error: {}", unexpected),
+        }
+    }
+
+    pub fn env(&self) -> &Env { unsafe { Env::from_ptr(self.env) } }
+
+    /// Did this guard attach the thread (and will it detach the thread when dropped?)
+    pub fn attached(&self) -> bool { self.detach }
+}
+
+impl<'vm> Drop for AttachGuard<'vm> {
+    fn drop(&mut self) {
+        if !self.detach { return; }
+        let java_vm = self.vm.as_java_vm() as *mut JavaVM;
+        unsafe { (**java_vm).DetachCurrentThread.unwrap()(java_vm); }
+    }
+}
+
+
+
+/// Detaches the thread when it exits, if the VM it was attached to is still loaded.
+struct DetachOnThreadExit(GenVM);
+
+impl Drop for DetachOnThreadExit {
+    fn drop(&mut self) {
+        let vms = match VMS.read() { Ok(vms) => vms, Err(_) => return };
+        if !vms.is_loaded(self.0) { return; } // VM was unloaded - detaching would be a use-after-free
+        let java_vm = self.0.vm as *mut JavaVM;
+        unsafe { (**java_vm).DetachCurrentThread.unwrap()(java_vm); }
+    }
+}
+
+thread_local! {
+    static DETACH_ON_THREAD_EXIT : RefCell<Option<DetachOnThreadExit>> = const { RefCell::new(None) };
+}
+
+/// Called by `VM::with_env` after it attaches the current thread.
+pub(crate) fn detach_on_thread_exit(java_vm: *mut JavaVM) {
+    let gen_vm = match VMS.read().ok().and_then(|vms| vms.get_gen_vm(java_vm)) {
+        Some(gen_vm) => gen_vm,
+        None => return,
+    };
+    let _ = DETACH_ON_THREAD_EXIT.try_with(|detach| {
+        let mut detach = detach.borrow_mut();
+        if detach.as_ref().is_some_and(|d| d.0 == gen_vm) { return; } // Already registered
+        *detach = Some(DetachOnThreadExit(gen_vm)); // Any previous registration is for an unloaded VM, and drops as a no-op
+    });
+}
@@ -0,0 +1,45 @@
+use super::*;
+
+/// A [VmBackend] that allows any number of VMs to be loaded simultaniously - such as desktop tests creating and
+/// destroying VMs.
+///
+/// [VmBackend]:    trait.VmBackend.html
+#[derive(Default)]
+pub struct MultiVmBackend {
+    loaded: Vec<GenVM>, // Rarely more than a couple, so a linear search is fine
+}
+
+impl MultiVmBackend {
+    pub const fn new() -> Self {
+        Self { loaded: Vec::new() }
+    }
+}
+
+unsafe impl VmBackend for MultiVmBackend {
+    unsafe fn on_load(&mut self, vm: *const JavaVM) {
+        assert!(self.get_gen_vm(vm).is_none(), "VM was already loaded");
+        self.loaded.push(GenVM::new(vm));
+    }
+
+    // Safe - only invalidates existing VMs, doesn't actually use 'em or free them.
+    fn on_unload(&mut self, vm: *const JavaVM) {
+        let index = self.loaded.iter().position(|loaded| loaded.vm == vm).expect("VM wasn't loaded");
+        self.loaded.remove(index);
+    }
+
+    fn get_gen_vm(&self, vm: *const JavaVM) -> Option<GenVM> {
+        self.loaded.iter().find(|loaded| loaded.vm == vm).copied()
+    }
+
+    fn is_loaded(&self, vm: GenVM) -> bool {
+        self.loaded.contains(&vm)
+    }
+
+    fn any_loaded(&self) -> bool {
+        !self.loaded.is_empty()
+    }
+
+    fn sole_vm(&self) -> Option<GenVM> {
+        if self.loaded.len() == 1 { Some(self.loaded[0]) } else { None }
+    }
+}
@@ -1,42 +1,47 @@
 use super::*;
 
-pub(crate) struct SingleVmBackend {
-    current: GenVM
+/// A [VmBackend] that allows only one VM to be loaded at a time - such as on Android.
+///
+/// [VmBackend]:    trait.VmBackend.html
+pub struct SingleVmBackend {
+    current: Option<GenVM>,
 }
 
 impl SingleVmBackend {
     pub const fn new() -> Self {
-        Self { 
-            current: GenVM {
-                gen: 0,
-                vm:  null(),
-            }
-        }
+        Self { current: None }
     }
+}
+
+impl Default for SingleVmBackend {
+    fn default() -> Self { Self::new() }
+}
 
-    // Unsafe - by calling this, you assert that `vm` will be valid until you call on_unload and allow it to return.
-    pub unsafe fn on_load(&mut self, vm: *const JavaVM) {
-        assert_eq!(self.current.vm, null());
-        self.current.gen += 1;
-        self.current.vm = vm;
+unsafe impl VmBackend for SingleVmBackend {
+    unsafe fn on_load(&mut self, vm: *const JavaVM) {
+        assert!(self.current.is_none(), "SingleVmBackend only supports one loaded VM at a time");
+        self.current = Some(GenVM::new(vm));
     }
 
     // Safe - only invalidates existing VMs, doesn't actually use 'em or free them.
-    pub fn on_unload(&mut self, vm: *const JavaVM) {
-        assert_eq!(self.current.vm, vm);
-        self.current.gen += 1;
-        self.current.vm = null();
+    fn on_unload(&mut self, vm: *const JavaVM) {
+        assert_eq!(self.current.map(|current| current.vm), Some(vm));
+        self.current = None;
+    }
+
+    fn get_gen_vm(&self, vm: *const JavaVM) -> Option<GenVM> {
+        self.current.filter(|current| current.vm == vm)
+    }
+
+    fn is_loaded(&self, vm: GenVM) -> bool {
+        self.current == Some(vm)
     }
 
-    // Safe - validates against current VM state.
-    pub fn use_vm(&self, vm: GenVM, callback: impl FnOnce(&VM)) {
-        assert_eq!(self.current, vm);
-        callback(unsafe { VM::from_jni_local(&*vm.vm) });
+    fn any_loaded(&self) -> bool {
+        self.current.is_some()
     }
 
-    // Safe - validates against current VM state.
-    pub fn get_gen_vm(&self, vm: *mut JavaVM) -> GenVM {
-        assert_eq!(self.current.vm, vm);
+    fn sole_vm(&self) -> Option<GenVM> {
         self.current
     }
 }
@@ -0,0 +1,58 @@
+use super::*;
+
+/// Tracks which [VM]s are currently loaded, such that [Global]s etc. can soundly use the [VM] they belong to.
+///
+/// jni-glue uses [SingleVmBackend] by default, or [MultiVmBackend] if the "multi-vm" feature is enabled.  Use
+/// [set_vm_backend] to pick one at runtime instead.
+///
+/// **unsafe**:  [Global]s, [IdCache]s etc. use a [VM] whenever the backend says it's loaded, so implementations must
+/// answer truthfully:
+/// * `get_gen_vm`, `is_loaded` and `sole_vm` must only report VMs passed to `on_load` and not yet passed to
+///   `on_unload` - and only with the [GenVM] created for that load, never one from a previous load of the same VM.
+/// * `any_loaded` must return `true` whenever any VM is loaded.
+///
+/// [VM]:               ../struct.VM.html
+/// [Global]:           ../struct.Global.html
+/// [GenVM]:            ../struct.GenVM.html
+/// [IdCache]:          ../struct.IdCache.html
+/// [SingleVmBackend]:  struct.SingleVmBackend.html
+/// [MultiVmBackend]:   struct.MultiVmBackend.html
+/// [set_vm_backend]:   ../fn.set_vm_backend.html
+pub unsafe trait VmBackend : Send + Sync {
+    /// Called from `JNI_OnLoad` or `JavaVmBuilder::build`.
+    ///
+    /// **unsafe**:  By calling this, you assert that `vm` will be valid until you call `on_unload` and allow it to return.
+    unsafe fn on_load(&mut self, vm: *const JavaVM);
+
+    /// Called from `JNI_OnUnload` or when a `JavaVm` is dropped.  Invalidates the [GenVM] of `vm`.
+    ///
+    /// [GenVM]:    ../struct.GenVM.html
+    fn on_unload(&mut self, vm: *const JavaVM);
+
+    /// The current [GenVM] of `vm`, or `None` if `vm` isn't loaded.
+    ///
+    /// [GenVM]:    ../struct.GenVM.html
+    fn get_gen_vm(&self, vm: *const JavaVM) -> Option<GenVM>;
+
+    /// Is `vm` still loaded?
+    fn is_loaded(&self, vm: GenVM) -> bool;
+
+    /// Are any VMs loaded?
+    fn any_loaded(&self) -> bool;
+
+    /// The only loaded VM, or `None` if there are zero or several.  Used by `StaticEnvStyle::Implicit` codegen.
+    fn sole_vm(&self) -> Option<GenVM>;
+}
+
+impl dyn VmBackend {
+    // Safe - validates against current VM state, which VmBackend implementations must report truthfully.
+    pub(crate) fn use_vm<R>(&self, vm: GenVM, callback: impl FnOnce(&VM) -> R) -> R {
+        assert!(self.is_loaded(vm));
+        callback(unsafe { VM::from_jni_local(&*vm.vm) })
+    }
+
+    // Safe - validates against current VM state, which VmBackend implementations must report truthfully.
+    pub(crate) fn require_gen_vm(&self, vm: *const JavaVM) -> GenVM {
+        self.get_gen_vm(vm).expect("VM isn't loaded")
+    }
+}
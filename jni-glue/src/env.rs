@@ -61,7 +61,66 @@ impl Env {
         let err = unsafe { (**jni_env).GetJavaVM.unwrap()(jni_env, &mut vm) };
         assert_eq!(err, JNI_OK);
         assert_ne!(vm, null_mut());
-        VMS.read().unwrap().get_gen_vm(vm)
+        VMS.read().unwrap().require_gen_vm(vm)
+    }
+
+    // Local Reference Management
+
+    /// Ensure at least `capacity` more local references can be created in the current local frame.
+    ///
+    /// Panics if the VM can't provide them (an OutOfMemoryError.)
+    pub fn ensure_local_capacity(&self, capacity: usize) {
+        assert!(capacity <= std::i32::MAX as usize); // jint == i32
+        let env = self.as_jni_env();
+        let err = unsafe { (**env).EnsureLocalCapacity.unwrap()(env, capacity as jint) };
+        assert_eq!(err, JNI_OK);
+    }
+
+    /// Run `callback` in a new local reference frame (PushLocalFrame / PopLocalFrame) with room for at least `capacity`
+    /// local references.  Every local reference created inside the frame - including those that were leaked with
+    /// `Local::leak` or handed out as `Ref`s - is freed when `callback` returns (or panics.)
+    ///
+    /// Use [with_local_frame_returning] to return a `Local` to the outer frame.
+    ///
+    /// ```rust,no_run
+    /// # use jni_glue::*;
+    /// # fn example(env: &Env) {
+    /// for _ in 0..1_000_000 {
+    ///     unsafe { env.with_local_frame(16, |env| {
+    ///         // ...create and leak as many as 16 locals...
+    ///     }) };
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// **unsafe**:  No local reference created inside the frame may be used after `callback` returns.  Locals created
+    /// via the callback's `env` can't escape (`R` can't borrow `'frame`), but nothing stops `callback` from capturing an
+    /// outer `env` - or calling methods on objects from outside the frame - and creating locals tied to the outer `'env`
+    /// instead, which are freed by PopLocalFrame all the same.
+    ///
+    /// [with_local_frame_returning]:   #method.with_local_frame_returning
+    pub unsafe fn with_local_frame<R>(&self, capacity: usize, callback: impl for<'frame> FnOnce(&'frame Env) -> R) -> R {
+        let frame = LocalFrame::push(self, capacity);
+        let result = callback(self);
+        drop(frame);
+        result
+    }
+
+    /// Like [with_local_frame], but `callback` may return a single `Local`, which is moved to the outer frame.
+    ///
+    /// **unsafe**:  As with [with_local_frame], no other local reference created inside the frame may be used after
+    /// `callback` returns.
+    ///
+    /// [with_local_frame]: #method.with_local_frame
+    pub unsafe fn with_local_frame_returning<'env, Class: AsValidJObjectAndEnv>(&'env self, capacity: usize, callback: impl for<'frame> FnOnce(&'frame Env) -> Option<Local<'frame, Class>>) -> Option<Local<'env, Class>> {
+        let frame = LocalFrame::push(self, capacity);
+        let result = callback(self).map(Local::into_raw).unwrap_or(null_mut());
+        let result = frame.pop(result);
+        if result.is_null() {
+            None
+        } else {
+            Some(Local::from_env_object(self.as_jni_env(), result))
+        }
     }
 
     // String methods
@@ -86,6 +145,76 @@ impl Env {
         (**env).ReleaseStringChars.unwrap()(env, string, chars as *const _)
     }
 
+    // Direct Buffer Methods
+
+    /// NewDirectByteBuffer:  Create a `java.nio.ByteBuffer` referencing `memory`, without copying it.  Returns `None`
+    /// if the VM doesn't support direct buffers.
+    ///
+    /// **unsafe**:  Nothing ties the returned reference - or the Java object - to `memory`'s lifetime:  Java code may
+    /// stash the buffer away, or you might create a global reference to it.  Accessing the buffer after `memory` is
+    /// gone is a use-after-free, so make sure nothing does, or use [new_direct_byte_buffer_static] instead.
+    ///
+    /// [new_direct_byte_buffer_static]:    #method.new_direct_byte_buffer_static
+    pub unsafe fn new_direct_byte_buffer(&self, memory: &mut [u8]) -> Option<jobject> {
+        assert!(memory.len() <= std::i64::MAX as usize); // jlong == i64
+        let env = &self.0 as *const JNIEnv as *mut JNIEnv;
+        let buffer = (**env).NewDirectByteBuffer.unwrap()(env, memory.as_mut_ptr() as *mut c_void, memory.len() as jlong);
+        if buffer.is_null() {
+            (**env).ExceptionClear.unwrap()(env); // OutOfMemoryError, or direct buffers aren't supported
+            None
+        } else {
+            Some(buffer)
+        }
+    }
+
+    /// NewDirectByteBuffer:  Create a `java.nio.ByteBuffer` referencing `memory`, without copying it.  Returns `None`
+    /// if the VM doesn't support direct buffers.  Java code may keep the buffer around as long as it likes.
+    pub fn new_direct_byte_buffer_static(&self, memory: &'static mut [u8]) -> Option<jobject> {
+        unsafe { self.new_direct_byte_buffer(memory) }
+    }
+
+    /// GetDirectBufferAddress + GetDirectBufferCapacity:  The memory referenced by a direct `java.nio.ByteBuffer`
+    /// (ignoring position and limit), or `None` if `buffer` isn't a direct buffer (or the VM doesn't support direct
+    /// buffers.)
+    ///
+    /// **unsafe**:  `buffer` must be a valid reference to a `java.nio.ByteBuffer`, which must outlive the returned slice.
+    /// Java code (or other native code) must not write to the buffer while the slice is borrowed.
+    pub unsafe fn get_direct_buffer_address(&self, buffer: jobject) -> Option<&[u8]> {
+        let (address, capacity) = self.get_direct_buffer_address_capacity(buffer)?;
+        Some(std::slice::from_raw_parts(address, capacity))
+    }
+
+    /// GetDirectBufferAddress + GetDirectBufferCapacity:  The memory referenced by a direct `java.nio.ByteBuffer`
+    /// (ignoring position and limit), or `None` if `buffer` isn't a direct buffer (or the VM doesn't support direct
+    /// buffers.)
+    ///
+    /// **unsafe**:  `buffer` must be a valid reference to a `java.nio.ByteBuffer`, which must outlive the returned slice.
+    /// Nothing else - Java code, other native code, or other slices returned by this method - may access the buffer
+    /// while the slice is borrowed.
+    #[allow(clippy::mut_from_ref)]
+    pub unsafe fn get_direct_buffer_address_mut(&self, buffer: jobject) -> Option<&mut [u8]> {
+        let (address, capacity) = self.get_direct_buffer_address_capacity(buffer)?;
+        Some(std::slice::from_raw_parts_mut(address, capacity))
+    }
+
+    /// GetDirectBufferCapacity:  The capacity of a direct `java.nio.Buffer` (in elements, not bytes), or `None` if
+    /// `buffer` isn't a direct buffer (or the VM doesn't support direct buffers.)
+    ///
+    /// **unsafe**:  `buffer` must be a valid reference to a `java.nio.Buffer`.
+    pub unsafe fn get_direct_buffer_capacity(&self, buffer: jobject) -> Option<usize> {
+        let env = &self.0 as *const JNIEnv as *mut JNIEnv;
+        let capacity = (**env).GetDirectBufferCapacity.unwrap()(env, buffer);
+        if capacity < 0 { None } else { Some(capacity as usize) }
+    }
+
+    unsafe fn get_direct_buffer_address_capacity(&self, buffer: jobject) -> Option<(*mut u8, usize)> {
+        let env = &self.0 as *const JNIEnv as *mut JNIEnv;
+        let address = (**env).GetDirectBufferAddress.unwrap()(env, buffer) as *mut u8;
+        if address.is_null() { return None; }
+        let capacity = self.get_direct_buffer_capacity(buffer)?;
+        Some((address, capacity))
+    }
+
     // Query Methods
 
     pub unsafe fn require_class(&self, class: &str) -> jclass {
@@ -96,6 +225,34 @@ impl Env {
         class
     }
 
+    /// Is `object` an instance of `Class` (as identified by it's [JniType])?  `false` if `Class` can't be found.
+    ///
+    /// **unsafe**:  `object` must be a valid, non-null reference belonging to this env.
+    ///
+    /// [JniType]:  trait.JniType.html
+    pub unsafe fn is_instance_of<Class: AsValidJObjectAndEnv>(&self, object: jobject) -> bool {
+        let env = &self.0 as *const JNIEnv as *mut JNIEnv;
+        Class::static_with_jni_type(|jni_type| {
+            let class_name = jni_type_class_name(jni_type);
+            let class = (**env).FindClass.unwrap()(env, class_name.as_ptr() as *const c_char);
+            if class.is_null() {
+                (**env).ExceptionClear.unwrap()(env); // NoClassDefFoundError
+                return false;
+            }
+            let result = (**env).IsInstanceOf.unwrap()(env, object, class) == JNI_TRUE;
+            (**env).DeleteLocalRef.unwrap()(env, class);
+            result
+        })
+    }
+
+    /// IsSameObject:  Do `a` and `b` refer to the same Java object (or are both null)?
+    ///
+    /// **unsafe**:  `a` and `b` must be null or valid references belonging to this env.
+    pub unsafe fn is_same_object(&self, a: jobject, b: jobject) -> bool {
+        let env = &self.0 as *const JNIEnv as *mut JNIEnv;
+        (**env).IsSameObject.unwrap()(env, a, b) == JNI_TRUE
+    }
+
     pub unsafe fn require_method(&self, class: jclass, method: &str, descriptor: &str) -> jmethodID {
         debug_assert!(method.ends_with('\0'));
         debug_assert!(descriptor.ends_with('\0'));
@@ -421,6 +578,31 @@ impl Env {
         }
     }
 
+    // Null Checks
+
+    /// Turn an `Ok(None)` result into an `Err` holding a new `java.lang.NullPointerException` - used by methods annotated
+    /// as never returning null, so a broken promise is a well-defined error instead of a panic (or worse.)
+    ///
+    /// The NullPointerException is handed back as a `Local<E>` without any type check, so `E` must be a supertype of
+    /// `java.lang.NullPointerException` - in practice `java.lang.Throwable`, as used by generated bindings.
+    pub fn require_non_null<'env, R: AsValidJObjectAndEnv, E: ThrowableType>(&'env self, result: Result<Option<Local<'env, R>>, Local<'env, E>>) -> Result<Local<'env, R>, Local<'env, E>> {
+        match result {
+            Ok(Some(local)) => Ok(local),
+            Err(exception)  => Err(exception),
+            Ok(None)        => unsafe {
+                let env = &self.0 as *const JNIEnv as *mut JNIEnv;
+                let class = self.require_class("java/lang/NullPointerException\0");
+                let message = "method annotated @NonNull returned null\0";
+                (**env).ThrowNew.unwrap()(env, class, message.as_ptr() as *const c_char);
+                (**env).DeleteLocalRef.unwrap()(env, class);
+                let exception = (**env).ExceptionOccurred.unwrap()(env);
+                assert!(!exception.is_null()); // ThrowNew only fails if it couldn't construct the exception (OOM?)
+                (**env).ExceptionClear.unwrap()(env);
+                Err(Local::from_env_object(env, exception))
+            },
+        }
+    }
+
     // Instance Fields
 
     pub unsafe fn get_object_field<'env, R: AsValidJObjectAndEnv>(&'env self, this: jobject, field: jfieldID) -> Option<Local<'env, R>> {
@@ -633,3 +815,42 @@ impl Env {
         (**env).SetStaticDoubleField.unwrap()(env, class, field, value);
     }
 }
+
+/// Pops the local frame on drop, even when unwinding.
+struct LocalFrame<'env>(&'env Env);
+
+impl<'env> LocalFrame<'env> {
+    fn push(env: &'env Env, capacity: usize) -> Self {
+        assert!(capacity <= std::i32::MAX as usize); // jint == i32
+        let jnienv = env.as_jni_env();
+        let err = unsafe { (**jnienv).PushLocalFrame.unwrap()(jnienv, capacity as jint) };
+        assert_eq!(err, JNI_OK); // Only sane failure here is an OOM
+        LocalFrame(env)
+    }
+
+    /// Pop the frame, returning a new local reference to `result` in the outer frame (or null if `result` is null.)
+    fn pop(self, result: jobject) -> jobject {
+        let jnienv = self.0.as_jni_env();
+        std::mem::forget(self);
+        unsafe { (**jnienv).PopLocalFrame.unwrap()(jnienv, result) }
+    }
+}
+
+impl<'env> Drop for LocalFrame<'env> {
+    fn drop(&mut self) {
+        let jnienv = self.0.as_jni_env();
+        unsafe { (**jnienv).PopLocalFrame.unwrap()(jnienv, null_mut()); }
+    }
+}
+
+/// FindClass accepts "java/lang/String\0" and "[Ljava/lang/String;\0", but not "Ljava/lang/String;\0".  Generated
+/// classes' JniType strings are bare class names without the NUL ("java/lang/String"), so that's appended if missing.
+pub(crate) fn jni_type_class_name(jni_type: &str) -> std::borrow::Cow<'_, str> {
+    if jni_type.starts_with('L') && jni_type.ends_with(";\0") {
+        std::borrow::Cow::Owned(format!("{}\0", &jni_type[1..jni_type.len()-2]))
+    } else if jni_type.ends_with('\0') {
+        std::borrow::Cow::Borrowed(jni_type)
+    } else {
+        std::borrow::Cow::Owned(format!("{}\0", jni_type))
+    }
+}
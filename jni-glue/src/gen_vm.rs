@@ -1,12 +1,26 @@
 use super::*;
+use std::sync::atomic::*;
 
 /// A generation count + VM pointer.  Used to attempt to ensure you're not accidentally mixing and matching different VM
 /// instances, even if they happen to have the same pointer.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub(crate) struct GenVM {
+pub struct GenVM {
     pub(crate) gen: usize,
     pub(crate) vm:  *const JavaVM,
 }
 
 unsafe impl Send for GenVM {}
 unsafe impl Sync for GenVM {}
+
+impl GenVM {
+    /// A new generation of `vm`, distinct from every other `GenVM` - including those for previous VMs at the same address.
+    pub fn new(vm: *const JavaVM) -> Self {
+        static NEXT_GEN : AtomicUsize = AtomicUsize::new(1); // 0 is reserved for "unresolved" by IdCache
+        Self {
+            gen: NEXT_GEN.fetch_add(1, Ordering::Relaxed),
+            vm,
+        }
+    }
+
+    pub fn vm(&self) -> *const JavaVM { self.vm }
+}
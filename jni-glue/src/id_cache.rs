@@ -0,0 +1,179 @@
+use super::*;
+use std::sync::atomic::*;
+
+lazy_static! { // Mutex::new is not const
+    static ref RESOLVED : Mutex<Vec<&'static IdCache>> = Mutex::new(Vec::new());
+}
+
+/// A lazily resolved class + method/field ID, reused for the lifetime of the [VM] it was resolved against.
+///
+/// Autogenerated glue code declares one of these as a `static` per method/field, instead of calling `FindClass` +
+/// `Get[Static]{Method,Field}ID` every time the method is called or the field is accessed.  The class is pinned with a
+/// global reference (keeping the IDs valid), which is released again when the [VM] is unloaded (`JNI_OnUnload`.)
+/// Each [VM] generation gets its own entry, so several VMs can use the same cache concurrently.
+///
+/// [VM]:   struct.VM.html
+pub struct IdCache {
+    lock:   AtomicBool, // Serializes resolving and unloading - lookups never take it.
+    head:   AtomicPtr<IdCacheEntry>,
+}
+
+/// The class + ID resolved against a single VM generation.  Entries are never freed (IdCaches are `static`s) - only
+/// cleared when their VM is unloaded, and reused for later VMs - so lookups can walk the list without locking.
+struct IdCacheEntry {
+    gen:    AtomicUsize, // GenVM::gen of the VM `class` and `id` belong to, or 0 if unused.  Generations are never reused,
+                         // so this doubles as a seqlock for `class` and `id`.
+    class:  AtomicPtr<_jobject>,
+    id:     AtomicUsize,
+    next:   *mut IdCacheEntry, // Immutable once published
+}
+
+impl IdCache {
+    pub const fn new() -> Self {
+        Self {
+            lock:   AtomicBool::new(false),
+            head:   AtomicPtr::new(null_mut()),
+        }
+    }
+
+    pub unsafe fn require_class_method(&'static self, env: &Env, class: &str, method: &str, descriptor: &str) -> (jclass, jmethodID) {
+        let (class, method) = self.require(env, class, |class| env.require_method(class, method, descriptor) as usize);
+        (class, method as jmethodID)
+    }
+
+    pub unsafe fn require_class_static_method(&'static self, env: &Env, class: &str, method: &str, descriptor: &str) -> (jclass, jmethodID) {
+        let (class, method) = self.require(env, class, |class| env.require_static_method(class, method, descriptor) as usize);
+        (class, method as jmethodID)
+    }
+
+    pub unsafe fn require_class_field(&'static self, env: &Env, class: &str, field: &str, descriptor: &str) -> (jclass, jfieldID) {
+        let (class, field) = self.require(env, class, |class| env.require_field(class, field, descriptor) as usize);
+        (class, field as jfieldID)
+    }
+
+    pub unsafe fn require_class_static_field(&'static self, env: &Env, class: &str, field: &str, descriptor: &str) -> (jclass, jfieldID) {
+        let (class, field) = self.require(env, class, |class| env.require_static_field(class, field, descriptor) as usize);
+        (class, field as jfieldID)
+    }
+
+    unsafe fn require(&'static self, env: &Env, class: &str, resolve_id: impl FnOnce(jclass) -> usize) -> (jclass, usize) {
+        let gen = env.get_gen_vm().gen;
+        if let Some(resolved) = self.get(gen) {
+            return resolved;
+        }
+
+        // Resolve outside of the lock: GetStatic{Method,Field}ID may run static initializers, which may reenter us.
+        let jni_env = env.as_jni_env();
+        let local = env.require_class(class);
+        let id = resolve_id(local);
+        let global = (**jni_env).NewGlobalRef.unwrap()(jni_env, local);
+        (**jni_env).DeleteLocalRef.unwrap()(jni_env, local);
+        assert!(!global.is_null());
+
+        let (resolved, first) = self.with_lock(|| {
+            if let Some(resolved) = self.get(gen) {
+                // Another thread beat us to it.
+                (**jni_env).DeleteGlobalRef.unwrap()(jni_env, global);
+                return (resolved, false);
+            }
+            let first = self.head.load(Ordering::Relaxed).is_null();
+            self.insert_locked(gen, global, id);
+            ((global, id), first)
+        });
+        if first { RESOLVED.lock().unwrap().push(self); } // N.B. outside self.lock, as on_unload locks in the opposite order
+        resolved
+    }
+
+    fn entries(&self) -> impl Iterator<Item = &IdCacheEntry> {
+        let mut next = self.head.load(Ordering::Acquire);
+        std::iter::from_fn(move || {
+            let entry = unsafe { next.as_ref() }?;
+            next = entry.next;
+            Some(entry)
+        })
+    }
+
+    /// The class + ID resolved against `gen`, if any.
+    fn get(&self, gen: usize) -> Option<(jclass, usize)> {
+        for entry in self.entries() {
+            if entry.gen.load(Ordering::Acquire) != gen { continue; }
+            let class = entry.class.load(Ordering::Relaxed);
+            let id = entry.id.load(Ordering::Relaxed);
+            fence(Ordering::Acquire);
+            if entry.gen.load(Ordering::Relaxed) == gen { return Some((class, id)); } // else unloaded while reading
+        }
+        None
+    }
+
+    /// Record `class` + `id` as resolved against `gen`, reusing an unloaded entry if possible.  Requires `self.lock`.
+    fn insert_locked(&self, gen: usize, class: jclass, id: usize) {
+        if let Some(entry) = self.entries().find(|entry| entry.gen.load(Ordering::Relaxed) == 0) {
+            entry.class.store(class, Ordering::Relaxed);
+            entry.id.store(id, Ordering::Relaxed);
+            entry.gen.store(gen, Ordering::Release);
+        } else {
+            let entry = Box::into_raw(Box::new(IdCacheEntry {
+                gen:    AtomicUsize::new(gen),
+                class:  AtomicPtr::new(class),
+                id:     AtomicUsize::new(id),
+                next:   self.head.load(Ordering::Relaxed),
+            }));
+            self.head.store(entry, Ordering::Release);
+        }
+    }
+
+    /// Forget what was resolved against `gen`, returning the class to release.  Requires `self.lock`.
+    fn forget_locked(&self, gen: usize) -> Option<jclass> {
+        let entry = self.entries().find(|entry| entry.gen.load(Ordering::Relaxed) == gen)?;
+        entry.gen.store(0, Ordering::Relaxed);
+        fence(Ordering::Release);
+        entry.id.store(0, Ordering::Relaxed);
+        Some(entry.class.swap(null_mut(), Ordering::Relaxed))
+    }
+
+    // A spinlock instead of a Mutex:  Mutex::new isn't const (and we'd rather not lazy_static! one per method/field.)
+    // It's only taken on the slow paths - resolving, or unloading a VM - and is only ever held for a few atomic stores
+    // and at most one DeleteGlobalRef, never across anything that might block or reenter us.  Contention is thus rare
+    // and brief, so yielding to whoever holds it is enough.
+    fn with_lock<R>(&self, f: impl FnOnce() -> R) -> R {
+        while self.lock.compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed).is_err() {
+            std::thread::yield_now();
+        }
+        let r = f();
+        self.lock.store(false, Ordering::Release);
+        r
+    }
+
+    /// Forget everything resolved against `gen_vm`, releasing our global class references if we're still able to.
+    pub(crate) fn on_unload(gen_vm: GenVM) {
+        let java_vm = gen_vm.vm as *mut JavaVM;
+        let mut env = null_mut();
+        let attached = unsafe { (**java_vm).GetEnv.unwrap()(java_vm, &mut env, JNI_VERSION_1_2) } == JNI_OK;
+        let env = env as *mut JNIEnv;
+
+        for cache in RESOLVED.lock().unwrap().iter() {
+            if let Some(class) = cache.with_lock(|| cache.forget_locked(gen_vm.gen)) {
+                if attached { unsafe { (**env).DeleteGlobalRef.unwrap()(env, class); } }
+            }
+        }
+    }
+}
+
+#[test] fn id_cache_entry_per_gen() {
+    let cache = IdCache::new();
+    let (a, b, c) = (1 as jclass, 2 as jclass, 3 as jclass);
+    cache.with_lock(|| { cache.insert_locked(1, a, 10); cache.insert_locked(2, b, 20); });
+    assert_eq!(cache.get(1), Some((a, 10)));
+    assert_eq!(cache.get(2), Some((b, 20)));
+    assert_eq!(cache.get(3), None);
+
+    assert_eq!(cache.with_lock(|| cache.forget_locked(1)), Some(a));
+    assert_eq!(cache.with_lock(|| cache.forget_locked(1)), None);
+    assert_eq!(cache.get(1), None);
+    assert_eq!(cache.get(2), Some((b, 20)));
+
+    cache.with_lock(|| cache.insert_locked(3, c, 30)); // Reuses gen 1's entry
+    assert_eq!(cache.entries().count(), 2);
+    assert_eq!(cache.get(3), Some((c, 30)));
+    assert_eq!(cache.get(2), Some((b, 20)));
+}
@@ -0,0 +1,46 @@
+use super::*;
+
+
+
+/// Converts the result of a call that borrows an [Env] into one that doesn't - [Local]s become [Global]s.  Used by code
+/// generated with `static_env = "implicit"`, where the [Env] only lives for the duration of the call.
+///
+/// [Env]:      struct.Env.html
+/// [Local]:    struct.Local.html
+/// [Global]:   struct.Global.html
+#[doc(hidden)]
+pub trait Unlocal {
+    type Output;
+    fn unlocal(self) -> Self::Output;
+}
+
+impl<'env, Class: AsValidJObjectAndEnv> Unlocal for Local<'env, Class> {
+    type Output = Global<Class>;
+    fn unlocal(self) -> Self::Output { Global::from(self) }
+}
+
+impl<T: Unlocal> Unlocal for Option<T> {
+    type Output = Option<T::Output>;
+    fn unlocal(self) -> Self::Output { self.map(Unlocal::unlocal) }
+}
+
+impl<T: Unlocal, E: Unlocal> Unlocal for Result<T, E> {
+    type Output = Result<T::Output, E::Output>;
+    fn unlocal(self) -> Self::Output {
+        match self {
+            Ok(value)   => Ok(value.unlocal()),
+            Err(err)    => Err(err.unlocal()),
+        }
+    }
+}
+
+macro_rules! unlocal_as_is {
+    ( $($ty:ty),* ) => {$(
+        impl Unlocal for $ty {
+            type Output = $ty;
+            fn unlocal(self) -> Self::Output { self }
+        }
+    )*};
+}
+
+unlocal_as_is!((), bool, jchar, i8, i16, i32, i64, f32, f64);
@@ -0,0 +1,198 @@
+use super::*;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+
+
+/// An owned snapshot of a Java exception:  class name, message, stack trace, and cause chain.
+///
+/// Unlike a [Local]\<[ThrowableType]\>, this holds no JNI references, so it can outlive the [Env] it was thrown on, be
+/// sent between threads, and implements [std::error::Error].  Any thrown [Local] converts into this (or directly into a
+/// `Box<dyn Error>`), so `?` works in functions returning `Box<dyn Error>`, and `.map_err(JavaException::from)?` works
+/// for other error types:
+///
+/// ```rust,no_run
+/// # use jni_glue::*;
+/// # fn example<E: ThrowableType>(result: Result<(), Local<E>>) -> Result<(), Box<dyn std::error::Error>> {
+/// result?; // Err(Local<E>) => Err(Box<JavaException>)
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [Local]:                struct.Local.html
+/// [ThrowableType]:        trait.ThrowableType.html
+/// [Env]:                  struct.Env.html
+/// [std::error::Error]:    https://doc.rust-lang.org/std/error/trait.Error.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JavaException {
+    /// The exception's binary class name, as per `Class.getName()` (e.g. "java.lang.IllegalStateException".)
+    pub class_name:     String,
+
+    /// `Throwable.getMessage()`, if not null.
+    pub message:        Option<String>,
+
+    /// `Throwable.getStackTrace()`, innermost frame first.
+    pub stack_trace:    Vec<StackTraceElement>,
+
+    /// `Throwable.getCause()`, if not null.
+    pub cause:          Option<Box<JavaException>>,
+}
+
+/// An owned snapshot of a `java.lang.StackTraceElement`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StackTraceElement {
+    pub class_name:     String,
+    pub method_name:    String,
+    pub file_name:      Option<String>,
+
+    /// Negative if unavailable (-2 for native methods.)
+    pub line_number:    i32,
+}
+
+const MAX_CAUSE_DEPTH : usize = 32;
+
+impl JavaException {
+    /// **unsafe**:  `throwable` must be a valid, non-null reference to a java.lang.Throwable belonging to `env`, and there
+    /// must be no pending exception.  Any exceptions thrown while inspecting `throwable` are cleared and ignored.
+    pub unsafe fn from_env_throwable(env: &Env, throwable: jobject) -> Self {
+        Self::from_env_throwable_depth(env, throwable, 0)
+    }
+
+    unsafe fn from_env_throwable_depth(env: &Env, throwable: jobject, depth: usize) -> Self {
+        static GET_CLASS       : IdCache = IdCache::new();
+        static GET_NAME        : IdCache = IdCache::new();
+        static GET_MESSAGE     : IdCache = IdCache::new();
+        static GET_STACK_TRACE : IdCache = IdCache::new();
+        static GET_CAUSE       : IdCache = IdCache::new();
+
+        let class = call_object_method(env, throwable, &GET_CLASS, "java/lang/Object\0", "getClass\0", "()Ljava/lang/Class;\0");
+        let class_name = class.and_then(|class| {
+            let name = call_string_method(env, class, &GET_NAME, "java/lang/Class\0", "getName\0", "()Ljava/lang/String;\0");
+            delete_local_ref(env, class);
+            name
+        }).unwrap_or_else(|| "java.lang.Throwable".to_owned());
+
+        let message = call_string_method(env, throwable, &GET_MESSAGE, "java/lang/Throwable\0", "getMessage\0", "()Ljava/lang/String;\0");
+
+        let stack_trace = call_object_method(env, throwable, &GET_STACK_TRACE, "java/lang/Throwable\0", "getStackTrace\0", "()[Ljava/lang/StackTraceElement;\0").map(|frames| {
+            let jni_env = env.as_jni_env();
+            let len = (**jni_env).GetArrayLength.unwrap()(jni_env, frames);
+            let stack_trace = (0..len).filter_map(|index| {
+                let frame = (**jni_env).GetObjectArrayElement.unwrap()(jni_env, frames, index);
+                if frame.is_null() { return None; }
+                let frame_data = StackTraceElement::from_env_object(env, frame);
+                delete_local_ref(env, frame);
+                Some(frame_data)
+            }).collect();
+            delete_local_ref(env, frames);
+            stack_trace
+        }).unwrap_or_default();
+
+        let cause = if depth >= MAX_CAUSE_DEPTH { None } else {
+            call_object_method(env, throwable, &GET_CAUSE, "java/lang/Throwable\0", "getCause\0", "()Ljava/lang/Throwable;\0").and_then(|cause| {
+                let jni_env = env.as_jni_env();
+                let is_self = (**jni_env).IsSameObject.unwrap()(jni_env, throwable, cause) != JNI_FALSE;
+                let result = if is_self { None } else { Some(Box::new(Self::from_env_throwable_depth(env, cause, depth + 1))) };
+                delete_local_ref(env, cause);
+                result
+            })
+        };
+
+        Self { class_name, message, stack_trace, cause }
+    }
+}
+
+impl StackTraceElement {
+    unsafe fn from_env_object(env: &Env, frame: jobject) -> Self {
+        static GET_CLASS_NAME  : IdCache = IdCache::new();
+        static GET_METHOD_NAME : IdCache = IdCache::new();
+        static GET_FILE_NAME   : IdCache = IdCache::new();
+        static GET_LINE_NUMBER : IdCache = IdCache::new();
+
+        let class = "java/lang/StackTraceElement\0";
+        let line_number = {
+            let (_, method) = GET_LINE_NUMBER.require_class_method(env, class, "getLineNumber\0", "()I\0");
+            env.call_int_method_a::<JavaExceptionThrowable>(frame, method, null()).unwrap_or(-1)
+        };
+
+        Self {
+            class_name:     call_string_method(env, frame, &GET_CLASS_NAME,  class, "getClassName\0",  "()Ljava/lang/String;\0").unwrap_or_default(),
+            method_name:    call_string_method(env, frame, &GET_METHOD_NAME, class, "getMethodName\0", "()Ljava/lang/String;\0").unwrap_or_default(),
+            file_name:      call_string_method(env, frame, &GET_FILE_NAME,   class, "getFileName\0",   "()Ljava/lang/String;\0"),
+            line_number,
+        }
+    }
+}
+
+impl Display for JavaException {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match self.message.as_ref() {
+            Some(message)   => write!(fmt, "{}: {}", &self.class_name, message),
+            None            => write!(fmt, "{}", &self.class_name),
+        }
+    }
+}
+
+impl Display for StackTraceElement {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        write!(fmt, "{}.{}(", &self.class_name, &self.method_name)?;
+        match (self.file_name.as_ref(), self.line_number) {
+            (_, -2)                                 => write!(fmt, "Native Method")?,
+            (Some(file), line) if line >= 0         => write!(fmt, "{}:{}", file, line)?,
+            (Some(file), _)                         => write!(fmt, "{}", file)?,
+            (None, _)                               => write!(fmt, "Unknown Source")?,
+        }
+        write!(fmt, ")")
+    }
+}
+
+impl Error for JavaException {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.cause.as_ref().map(|cause| &**cause as &(dyn Error + 'static))
+    }
+}
+
+impl<'env, E: ThrowableType> From<Local<'env, E>> for JavaException {
+    fn from(throwable: Local<'env, E>) -> Self {
+        unsafe { JavaException::from_env_throwable(Env::from_ptr(throwable.oae.env), throwable.oae.object) }
+    }
+}
+
+impl<'env, E: ThrowableType> From<Local<'env, E>> for Box<dyn Error> {
+    fn from(throwable: Local<'env, E>) -> Self { Box::new(JavaException::from(throwable)) }
+}
+
+impl<'env, E: ThrowableType> From<Local<'env, E>> for Box<dyn Error + Send + Sync> {
+    fn from(throwable: Local<'env, E>) -> Self { Box::new(JavaException::from(throwable)) }
+}
+
+
+
+// Minimal glue for calling into java.lang.Throwable & co. without generated bindings.
+
+#[repr(transparent)] struct JavaExceptionThrowable(ObjectAndEnv);
+unsafe impl AsValidJObjectAndEnv for JavaExceptionThrowable {}
+unsafe impl AsJValue for JavaExceptionThrowable { fn as_jvalue(&self) -> jvalue { jvalue { l: self.0.object } } }
+unsafe impl JniType for JavaExceptionThrowable { fn static_with_jni_type<R>(callback: impl FnOnce(&str) -> R) -> R { callback("java/lang/Throwable\0") } }
+impl ThrowableType for JavaExceptionThrowable {}
+
+unsafe fn call_object_method(env: &Env, this: jobject, cache: &'static IdCache, class: &str, method: &str, descriptor: &str) -> Option<jobject> {
+    let (_, method) = cache.require_class_method(env, class, method, descriptor);
+    match env.call_object_method_a::<JavaExceptionThrowable, JavaExceptionThrowable>(this, method, null()) {
+        Ok(Some(result))    => Some(Local::into_raw(result)),
+        Ok(None)            => None,
+        Err(_)              => None, // Nested exception dropped (DeleteLocalRef)
+    }
+}
+
+unsafe fn call_string_method(env: &Env, this: jobject, cache: &'static IdCache, class: &str, method: &str, descriptor: &str) -> Option<String> {
+    let string = call_object_method(env, this, cache, class, method, descriptor)?;
+    let result = StringChars::from_env_jstring(env, string).to_string_lossy();
+    delete_local_ref(env, string);
+    Some(result)
+}
+
+unsafe fn delete_local_ref(env: &Env, object: jobject) {
+    let env = env.as_jni_env();
+    (**env).DeleteLocalRef.unwrap()(env, object);
+}
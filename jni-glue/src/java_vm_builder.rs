@@ -0,0 +1,163 @@
+use super::*;
+use std::env;
+use std::io;
+use std::mem::ManuallyDrop;
+use std::path::{Path, PathBuf};
+
+
+
+/// Creates a [JavaVm] from Rust via the [Invocation API](https://docs.oracle.com/javase/8/docs/technotes/guides/jni/spec/invocation.html),
+/// instead of waiting for Java to load us and call `JNI_OnLoad`.  Requires the `invocation` feature.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use jni_glue::*;
+/// let vm = JavaVmBuilder::new()
+///     .classpath("target/classes")
+///     .option("-Xmx256m")
+///     .build()
+///     .expect("Unable to create JVM");
+///
+/// vm.with_env(|env| {
+///     // Call generated bindings with env...
+/// });
+/// ```
+///
+/// [JavaVm]:   struct.JavaVm.html
+#[derive(Clone, Debug, Default)]
+pub struct JavaVmBuilder {
+    libjvm:                 Option<PathBuf>,
+    classpath:              Vec<PathBuf>,
+    options:                Vec<String>,
+    ignore_unrecognized:    bool,
+}
+
+impl JavaVmBuilder {
+    pub fn new() -> Self { Self::default() }
+
+    /// Load the JVM from this `libjvm.so` / `libjvm.dylib` / `jvm.dll` instead of searching `JAVA_HOME`.
+    pub fn libjvm(mut self, path: impl Into<PathBuf>) -> Self { self.libjvm = Some(path.into()); self }
+
+    /// Append a directory or .jar to `-Djava.class.path`.
+    pub fn classpath(mut self, path: impl Into<PathBuf>) -> Self { self.classpath.push(path.into()); self }
+
+    /// Add a raw JVM option, such as `"-Xmx256m"`, `"-Xcheck:jni"`, or `"-Dkey=value"`.
+    pub fn option(mut self, option: impl Into<String>) -> Self { self.options.push(option.into()); self }
+
+    /// Ignore unrecognized options beginning with `-X` or `_`, instead of failing to create the JVM.
+    pub fn ignore_unrecognized(mut self, ignore: bool) -> Self { self.ignore_unrecognized = ignore; self }
+
+    /// Load libjvm, create the JVM, and register it with jni-glue such that [Global]s etc. work against it.
+    ///
+    /// Most JVMs only support creating a single JVM per process, ever - even after the first has been destroyed.
+    ///
+    /// [Global]:   struct.Global.html
+    pub fn build(self) -> io::Result<JavaVm> {
+        let libjvm = match self.libjvm {
+            Some(path)  => path,
+            None        => find_libjvm()?,
+        };
+
+        let library = unsafe { libloading::Library::new(&libjvm) }
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, format!("Unable to load {}: {}", libjvm.display(), err)))?;
+        let create : libloading::Symbol<unsafe extern "system" fn(*mut *mut JavaVM, *mut *mut c_void, *mut c_void) -> jint> = unsafe { library.get(b"JNI_CreateJavaVM\0") }
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, format!("Unable to find JNI_CreateJavaVM in {}: {}", libjvm.display(), err)))?;
+
+        let mut options = Vec::new();
+        if !self.classpath.is_empty() {
+            let classpath = env::join_paths(self.classpath.iter())
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid classpath: {}", err)))?;
+            options.push(format!("-Djava.class.path={}", classpath.to_string_lossy()));
+        }
+        options.extend(self.options);
+
+        let options = options.into_iter().map(|option| CString::new(option).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "JVM option contained '\\0'")))
+            .collect::<io::Result<Vec<CString>>>()?;
+        let mut jni_options = options.iter().map(|option| JavaVMOption {
+            optionString:   option.as_ptr() as *mut _,
+            extraInfo:      null_mut(),
+        }).collect::<Vec<_>>();
+
+        let mut args = JavaVMInitArgs {
+            version:            JNI_VERSION_1_8,
+            nOptions:           jni_options.len() as jint,
+            options:            jni_options.as_mut_ptr(),
+            ignoreUnrecognized: if self.ignore_unrecognized { JNI_TRUE } else { JNI_FALSE },
+        };
+
+        let mut vm = null_mut();
+        let mut env = null_mut();
+        match unsafe { create(&mut vm, &mut env, &mut args as *mut JavaVMInitArgs as *mut c_void) } {
+            JNI_OK => {},
+            err => return Err(io::Error::new(io::ErrorKind::Other, format!("JNI_CreateJavaVM failed: {}", jni_error_name(err)))),
+        }
+
+        unsafe { VMS.write().unwrap().on_load(vm) };
+        Ok(JavaVm {
+            _library: ManuallyDrop::new(library),
+            vm,
+        })
+    }
+}
+
+/// A JVM created and owned by Rust via [JavaVmBuilder].  Unregistered from jni-glue and destroyed when dropped.
+///
+/// [JavaVmBuilder]:    struct.JavaVmBuilder.html
+pub struct JavaVm {
+    _library:   ManuallyDrop<libloading::Library>, // Never unloaded - JVMs generally don't support it, and may leave threads behind
+    vm:         *mut JavaVM,
+}
+
+unsafe impl Send for JavaVm {}
+unsafe impl Sync for JavaVm {}
+
+impl JavaVm {
+    pub fn vm(&self) -> &VM { unsafe { VM::from_jni_local(&*self.vm) } }
+
+    /// Run `callback` with an [Env] for the current thread, attaching it to the JVM if necessary.
+    ///
+    /// [Env]:  struct.Env.html
+    pub fn with_env<F, R>(&self, callback: F) -> R
+    where
+        F: FnOnce(&Env) -> R,
+    {
+        self.vm().with_env(callback)
+    }
+}
+
+impl Drop for JavaVm {
+    fn drop(&mut self) {
+        unload_impl(self.vm);
+        unsafe { (**self.vm).DestroyJavaVM.unwrap()(self.vm); }
+    }
+}
+
+fn find_libjvm() -> io::Result<PathBuf> {
+    let java_home = env::var_os("JAVA_HOME").ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Unable to find libjvm: JAVA_HOME is not set"))?;
+    let java_home = Path::new(&java_home);
+
+    let candidates = [
+        "lib/server/libjvm.so",             // Linux, JDK 9+
+        "jre/lib/amd64/server/libjvm.so",   // Linux, JDK 8
+        "lib/server/libjvm.dylib",          // MacOS, JDK 9+
+        "jre/lib/server/libjvm.dylib",      // MacOS, JDK 8
+        "bin/server/jvm.dll",               // Windows, JDK 9+
+        "jre/bin/server/jvm.dll",           // Windows, JDK 8
+    ];
+
+    candidates.iter().map(|candidate| java_home.join(candidate)).find(|path| path.exists())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("Unable to find libjvm in JAVA_HOME ({})", java_home.display())))
+}
+
+fn jni_error_name(err: jint) -> String {
+    match err {
+        JNI_ERR         => "JNI_ERR".to_owned(),
+        JNI_EDETACHED   => "JNI_EDETACHED".to_owned(),
+        JNI_EVERSION    => "JNI_EVERSION".to_owned(),
+        JNI_ENOMEM      => "JNI_ENOMEM".to_owned(),
+        JNI_EEXIST      => "JNI_EEXIST (a JVM already exists in this process)".to_owned(),
+        JNI_EINVAL      => "JNI_EINVAL".to_owned(),
+        other           => format!("{}", other),
+    }
+}
@@ -14,14 +14,22 @@ use std::ffi::*;
 use std::ptr::*;
 use std::marker::PhantomData;
 use std::ops::Deref;
-use std::sync::*;
+use std::sync::{Mutex, RwLock};
 
-pub(crate) mod backends { // XXX: Might expose this to the end user in order to let them choose which backend to use...?
+/// [VmBackend]s track which VMs are loaded.  See [set_vm_backend].
+///
+/// [VmBackend]:        trait.VmBackend.html
+/// [set_vm_backend]:   ../fn.set_vm_backend.html
+pub mod backends {
     use super::*;
 
+    mod multi_vm_backend;
     mod single_vm_backend;
+    mod vm_backend;
 
-    pub(crate) use single_vm_backend::*;
+    pub use multi_vm_backend::*;
+    pub use single_vm_backend::*;
+    pub use vm_backend::*;
 }
 
 mod refs {
@@ -31,19 +39,26 @@ mod refs {
     mod global;
     mod local;
     mod ref_;
+    mod weak;
 
     pub use argument::*;
     pub use global::*;
     pub use local::*;
     pub use ref_::*;
+    pub use weak::*;
 }
 
 mod __jni_bindgen;
 mod array;
 mod as_jvalue;
 mod as_valid_jobject_and_env;
+mod attach_guard;
 mod env;
 mod gen_vm;
+mod id_cache;
+mod implicit;
+mod java_exception;
+#[cfg(feature = "invocation")] mod java_vm_builder;
 mod jchar_;
 mod jni_type;
 mod object_and_env;
@@ -54,8 +69,13 @@ mod vm;
 pub use array::*;
 pub use as_jvalue::*;
 pub use as_valid_jobject_and_env::*;
+pub use attach_guard::*;
 pub use env::*;
-pub(crate) use gen_vm::*;
+pub use gen_vm::*;
+pub use id_cache::*;
+pub use implicit::*;
+pub use java_exception::*;
+#[cfg(feature = "invocation")] pub use java_vm_builder::*;
 pub use jchar_::{jchar, *};
 pub use jni_type::JniType;
 pub use object_and_env::*;
@@ -66,9 +86,23 @@ pub use vm::*;
 
 
 
-type VmBackend = backends::SingleVmBackend;
+#[cfg(not(feature = "multi-vm"))] type DefaultVmBackend = backends::SingleVmBackend;
+#[cfg(feature = "multi-vm")]      type DefaultVmBackend = backends::MultiVmBackend;
 lazy_static! { // RwLock::new is not const
-    static ref VMS : RwLock<VmBackend> = RwLock::new(VmBackend::new());
+    static ref VMS : RwLock<Box<dyn backends::VmBackend>> = RwLock::new(Box::new(DefaultVmBackend::new()));
+}
+
+/// Replace the [VmBackend] used to track loaded VMs.  Panics if any VMs are currently loaded.
+///
+/// ```rust,no_run
+/// jni_glue::set_vm_backend(jni_glue::backends::MultiVmBackend::new());
+/// ```
+///
+/// [VmBackend]:    backends/trait.VmBackend.html
+pub fn set_vm_backend(backend: impl backends::VmBackend + 'static) {
+    let mut vms = VMS.write().unwrap();
+    assert!(!vms.any_loaded(), "set_vm_backend called while VMs were loaded");
+    *vms = Box::new(backend);
 }
 
 /// **Disable "unsafe-manual-jni-load-unload", or call from JNI_OnLoad, or there may be soundness issues!**
@@ -81,7 +115,7 @@ pub unsafe fn on_load(vm: *const JavaVM, _reserved: *const c_void) -> jint {
 /// **Disable "unsafe-manual-jni-load-unload", or call from JNI_OnUnload, or there will be soundness issues!**
 #[cfg(feature = "unsafe-manual-jni-load-unload")]
 pub fn on_unload(vm: *const JavaVM, _reserved: *const c_void) {
-    VMS.write().unwrap().on_unload(vm);
+    unload_impl(vm);
 }
 
 /// **Do not call!**  Automatically invoked by the JVM.  See "unsafe-manual-jni-load-unload" to override this behavior.
@@ -94,5 +128,11 @@ pub unsafe extern "system" fn JNI_OnLoad(vm: *const JavaVM, _reserved: *const c_
 /// **Do not call!**  Automatically invoked by the JVM.  See "unsafe-manual-jni-load-unload" to override this behavior.
 #[no_mangle] #[allow(non_snake_case)] #[cfg(not(feature = "unsafe-manual-jni-load-unload"))]
 pub extern "system" fn JNI_OnUnload(vm: *const JavaVM, _reserved: *const c_void) {
+    unload_impl(vm);
+}
+
+pub(crate) fn unload_impl(vm: *const JavaVM) {
+    let gen_vm = VMS.read().unwrap().require_gen_vm(vm);
+    IdCache::on_unload(gen_vm);
     VMS.write().unwrap().on_unload(vm);
 }
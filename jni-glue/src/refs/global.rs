@@ -43,14 +43,24 @@ impl<Class: AsValidJObjectAndEnv> Global<Class> {
             _class: PhantomData,
         }
     }
-}
 
-impl<'env, Class: AsValidJObjectAndEnv> From<Local<'env, Class>> for Global<Class> {
-    fn from(local: Local<'env, Class>) -> Global<Class> {
-        let env = unsafe { Env::from_ptr(local.oae.env) };
+    /// Create a new [Local] reference to this object via NewLocalRef.
+    ///
+    /// [Local]:    struct.Local.html
+    pub fn to_local<'env>(&self, env: &'env Env) -> Local<'env, Class> {
+        assert_eq!(self.gen_vm, env.get_gen_vm()); // Soundness check - env *must* belong to the same VM!
+        let jnienv = env.as_jni_env();
+        let object = unsafe { (**jnienv).NewLocalRef.unwrap()(jnienv, self.global) };
+        assert!(!object.is_null());
+        unsafe { Local::from_env_object(jnienv, object) }
+    }
+
+    /// NewGlobalRef `object` via `env`.
+    unsafe fn new_global_ref(env: &Env, object: jobject) -> Self {
         let jnienv = env.as_jni_env();
         let gen_vm = env.get_gen_vm();
-        let global = unsafe { (**jnienv).NewGlobalRef.unwrap()(jnienv, local.oae.object) };
+        let global = (**jnienv).NewGlobalRef.unwrap()(jnienv, object);
+        assert!(!global.is_null()); // Only fails on OOM, or if the object was a weak global that got collected
         Global {
             global,
             gen_vm,
@@ -59,6 +69,43 @@ impl<'env, Class: AsValidJObjectAndEnv> From<Local<'env, Class>> for Global<Clas
     }
 }
 
+impl<Class: AsValidJObjectAndEnv> Clone for Global<Class> {
+    /// Create a new global reference to the same object via NewGlobalRef, attaching the current thread if necessary.
+    fn clone(&self) -> Self {
+        let mut result = None;
+        VMS.read().unwrap().use_vm(self.gen_vm, |vm|{
+            vm.with_env(|env|{
+                result = Some(unsafe { Self::new_global_ref(env, self.global) });
+            });
+        });
+        result.unwrap()
+    }
+}
+
+impl<'env, Class: AsValidJObjectAndEnv> From<Local<'env, Class>> for Global<Class> {
+    fn from(local: Local<'env, Class>) -> Global<Class> {
+        Self::from(&local)
+    }
+}
+
+impl<'local, 'env, Class: AsValidJObjectAndEnv> From<&'local Local<'env, Class>> for Global<Class> {
+    fn from(local: &'local Local<'env, Class>) -> Global<Class> {
+        unsafe { Self::new_global_ref(Env::from_ptr(local.oae.env), local.oae.object) }
+    }
+}
+
+impl<'env, Class: AsValidJObjectAndEnv> From<Ref<'env, Class>> for Global<Class> {
+    fn from(r: Ref<'env, Class>) -> Global<Class> {
+        Self::from(&r)
+    }
+}
+
+impl<'r, 'env, Class: AsValidJObjectAndEnv> From<&'r Ref<'env, Class>> for Global<Class> {
+    fn from(r: &'r Ref<'env, Class>) -> Global<Class> {
+        unsafe { Self::new_global_ref(Env::from_ptr(r.oae.env), r.oae.object) }
+    }
+}
+
 impl<Class: AsValidJObjectAndEnv> Drop for Global<Class> {
     fn drop(&mut self) {
         VMS.read().unwrap().use_vm(self.gen_vm, |vm|{
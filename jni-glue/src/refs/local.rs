@@ -33,7 +33,6 @@ pub struct Local<'env, Class: AsValidJObjectAndEnv> {
     pub(crate) _class: PhantomData<&'env Class>,
 }
 
-// Could implement clone if necessary via NewLocalRef
 // Do *not* implement Copy, cannot be safely done.
 
 impl<'env, Class: AsValidJObjectAndEnv> Local<'env, Class> {
@@ -57,6 +56,73 @@ impl<'env, Class: AsValidJObjectAndEnv> Local<'env, Class> {
         std::mem::forget(local); // Don't allow local to DeleteLocalRef the jobject
         result
     }
+
+    /// Is this object an instance of `U` (a subclass, implementor of the interface, etc.)?
+    ///
+    /// This is an associated function (`Local::is_instance_of::<U>(&local)`) so it can't be shadowed by - or shadow -
+    /// a Java method of the same name.
+    pub fn is_instance_of<U: AsValidJObjectAndEnv>(local: &Self) -> bool {
+        unsafe { Env::from_ptr(local.oae.env).is_instance_of::<U>(local.oae.object) }
+    }
+
+    /// Downcast (or cross-cast) to `U` if this object is an instance of it, as per `instanceof`.  Otherwise `None`, and
+    /// the local reference is dropped.
+    ///
+    /// ```rust,no_run
+    /// # use jni_glue::*;
+    /// # fn example<Object: AsValidJObjectAndEnv, String: AsValidJObjectAndEnv>(object: Local<Object>) {
+    /// if let Some(string) = Local::cast::<String>(object) {
+    ///     // ...
+    /// }
+    /// # }
+    /// ```
+    pub fn cast<U: AsValidJObjectAndEnv>(local: Self) -> Option<Local<'env, U>> {
+        if !Self::is_instance_of::<U>(&local) { return None; }
+        let result = Local {
+            oae: ObjectAndEnv {
+                object: local.oae.object,
+                env:    local.oae.env,
+            },
+            _env:   PhantomData,
+            _class: PhantomData,
+        };
+        std::mem::forget(local); // Ownership of the jobject moves to result
+        Some(result)
+    }
+
+    /// Leak the local reference as a raw jobject - e.g. to return it from a JNI callback, where Java takes ownership.
+    pub fn into_raw(local: Self) -> jobject {
+        let object = local.oae.object;
+        std::mem::forget(local); // Don't allow local to DeleteLocalRef the jobject
+        object
+    }
+}
+
+impl<'env, Class: AsValidJObjectAndEnv> Clone for Local<'env, Class> {
+    /// Create a new local reference to the same object via NewLocalRef.
+    fn clone(&self) -> Self {
+        let env = self.oae.env as *mut JNIEnv;
+        let object = unsafe { (**env).NewLocalRef.unwrap()(env, self.oae.object) };
+        assert!(!object.is_null()); // Only fails on OOM, or if the object was a weak global that got collected
+        unsafe { Self::from_env_object(env, object) }
+    }
+}
+
+impl<'env, Class: AsValidJObjectAndEnv> From<Ref<'env, Class>> for Local<'env, Class> {
+    /// Create a new local reference to the same object via NewLocalRef.
+    fn from(r: Ref<'env, Class>) -> Self {
+        Self::from(&r)
+    }
+}
+
+impl<'r, 'env, Class: AsValidJObjectAndEnv> From<&'r Ref<'env, Class>> for Local<'env, Class> {
+    /// Create a new local reference to the same object via NewLocalRef.
+    fn from(r: &'r Ref<'env, Class>) -> Self {
+        let env = r.oae.env as *mut JNIEnv;
+        let object = unsafe { (**env).NewLocalRef.unwrap()(env, r.oae.object) };
+        assert!(!object.is_null());
+        unsafe { Self::from_env_object(env, object) }
+    }
 }
 
 impl<'env, Class: AsValidJObjectAndEnv> Deref for Local<'env, Class> {
@@ -20,6 +20,42 @@ pub struct Ref<'env, Class: AsValidJObjectAndEnv> {
     pub(crate) _class: PhantomData<&'env Class>,
 }
 
+impl<'env, Class: AsValidJObjectAndEnv> Ref<'env, Class> {
+    /// Is this object an instance of `U` (a subclass, implementor of the interface, etc.)?
+    pub fn is_instance_of<U: AsValidJObjectAndEnv>(r: &Self) -> bool {
+        unsafe { Env::from_ptr(r.oae.env).is_instance_of::<U>(r.oae.object) }
+    }
+
+    /// Downcast (or cross-cast) to `U` if this object is an instance of it, as per `instanceof`.  Otherwise `None`.
+    pub fn try_cast<U: AsValidJObjectAndEnv>(r: &Self) -> Option<Ref<'env, U>> {
+        if !Self::is_instance_of::<U>(r) { return None; }
+        Some(Ref {
+            oae: ObjectAndEnv {
+                object: r.oae.object,
+                env:    r.oae.env,
+            },
+            _env:   PhantomData,
+            _class: PhantomData,
+        })
+    }
+}
+
+impl<'local, 'env: 'local, Class: AsValidJObjectAndEnv> From<&'local Local<'env, Class>> for Ref<'local, Class> {
+    /// Borrow a [Local] without creating a new reference.
+    ///
+    /// [Local]:    struct.Local.html
+    fn from(local: &'local Local<'env, Class>) -> Self {
+        Ref {
+            oae: ObjectAndEnv {
+                object: local.oae.object,
+                env:    local.oae.env,
+            },
+            _env:   PhantomData,
+            _class: PhantomData,
+        }
+    }
+}
+
 impl<'env, Class: AsValidJObjectAndEnv> Deref for Ref<'env, Class> {
     type Target = Class;
     fn deref(&self) -> &Self::Target {
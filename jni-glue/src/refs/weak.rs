@@ -0,0 +1,89 @@
+use super::*;
+
+
+
+/// A [Weak Global](https://www.ibm.com/support/knowledgecenter/en/SSYKE2_8.0.0/com.ibm.java.vm.80.doc/docs/jni_refs.html),
+/// reference to a Java object (+ &[VM]).
+///
+/// Like a [Global], this can be stored statically and shared between threads, but it doesn't keep the object alive.
+/// Use [upgrade] to get a [Local] to the object - if it hasn't been garbage collected yet.
+///
+/// ```rust,no_run
+/// # use jni_glue::*;
+/// # fn example<Class: AsValidJObjectAndEnv>(env: &Env, view: Local<Class>) {
+/// let weak = Weak::from(&view);
+/// drop(view);
+/// // ...
+/// if let Some(view) = weak.upgrade(env) {
+///     // ...
+/// }
+/// # }
+/// ```
+///
+/// **Not FFI Safe:**  #\[repr(rust)\], and exact layout is likely to change - depending on exact features used - in the
+/// future.
+///
+/// [VM]:           struct.VM.html
+/// [Global]:       struct.Global.html
+/// [Local]:        struct.Local.html
+/// [upgrade]:      #method.upgrade
+pub struct Weak<Class: AsValidJObjectAndEnv> {
+    pub(crate) weak:    jweak,
+    pub(crate) gen_vm:  GenVM,
+    pub(crate) pd:      PhantomData<Class>,
+}
+
+unsafe impl<Class: AsValidJObjectAndEnv> Send for Weak<Class> {}
+unsafe impl<Class: AsValidJObjectAndEnv> Sync for Weak<Class> {}
+
+impl<Class: AsValidJObjectAndEnv> Weak<Class> {
+    /// Get a new [Local] reference to the object, or `None` if it's been garbage collected.
+    ///
+    /// [Local]:    struct.Local.html
+    pub fn upgrade<'env>(&self, env: &'env Env) -> Option<Local<'env, Class>> {
+        assert_eq!(self.gen_vm, env.get_gen_vm()); // Soundness check - env *must* belong to the same VM!
+        let jnienv = env.as_jni_env();
+        let object = unsafe { (**jnienv).NewLocalRef.unwrap()(jnienv, self.weak) };
+        if object.is_null() {
+            None
+        } else {
+            Some(unsafe { Local::from_env_object(jnienv, object) })
+        }
+    }
+
+    /// NewWeakGlobalRef `object` via `env`.
+    unsafe fn new_weak_global_ref(env: &Env, object: jobject) -> Self {
+        let jnienv = env.as_jni_env();
+        let gen_vm = env.get_gen_vm();
+        let weak = (**jnienv).NewWeakGlobalRef.unwrap()(jnienv, object);
+        assert!(!weak.is_null()); // Only sane failure here is an OOM
+        Weak {
+            weak,
+            gen_vm,
+            pd: PhantomData,
+        }
+    }
+}
+
+impl<'local, 'env, Class: AsValidJObjectAndEnv> From<&'local Local<'env, Class>> for Weak<Class> {
+    fn from(local: &'local Local<'env, Class>) -> Weak<Class> {
+        unsafe { Self::new_weak_global_ref(Env::from_ptr(local.oae.env), local.oae.object) }
+    }
+}
+
+impl<'r, 'env, Class: AsValidJObjectAndEnv> From<&'r Ref<'env, Class>> for Weak<Class> {
+    fn from(r: &'r Ref<'env, Class>) -> Weak<Class> {
+        unsafe { Self::new_weak_global_ref(Env::from_ptr(r.oae.env), r.oae.object) }
+    }
+}
+
+impl<Class: AsValidJObjectAndEnv> Drop for Weak<Class> {
+    fn drop(&mut self) {
+        VMS.read().unwrap().use_vm(self.gen_vm, |vm|{
+            vm.with_env(|env|{
+                let env = env.as_jni_env();
+                unsafe { (**env).DeleteWeakGlobalRef.unwrap()(env, self.weak); }
+            });
+        });
+    }
+}
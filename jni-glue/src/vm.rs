@@ -3,9 +3,10 @@ use super::*;
 /// FFI: Use **&VM** instead of *const JavaVM.  This represents a global, process-wide Java exection environment.
 /// 
 /// On Android, there is only one VM per-process, although on desktop it's possible (if rare) to have multiple VMs
-/// within the same process.  While this library does not yet support having multiple VMs active simultaniously, please
-/// don't hesitate to [file an issue](https://github.com/MaulingMonkey/jni-bindgen/issues/new) if this is an important
-/// use case for you.
+/// within the same process.  By default this library only supports one VM being loaded at a time - enable the
+/// "multi-vm" feature or call [set_vm_backend] to support having multiple VMs active simultaniously.
+///
+/// [set_vm_backend]:   fn.set_vm_backend.html
 ///
 /// This is a "safe" alternative to jni_sys::JavaVM raw pointers, with the following caveats:
 /// 
@@ -23,6 +24,11 @@ impl VM {
     pub fn as_java_vm(&self) -> *const JavaVM { &self.0 }
     pub unsafe fn from_jni_local(vm: &JavaVM) -> &VM { &*(vm as *const JavaVM as *const VM) }
 
+    /// Run `callback` with an [Env] for the current thread.  If the thread isn't attached, it's attached until it exits
+    /// (use [attach] instead if you want to detach sooner.)
+    ///
+    /// [Env]:      struct.Env.html
+    /// [attach]:   #method.attach
     pub fn with_env<F, R>(&self, callback: F) -> R
     where
         F: FnOnce(&Env) -> R,
@@ -32,13 +38,49 @@ impl VM {
         match unsafe { (**java_vm).GetEnv.unwrap()(java_vm, &mut env, JNI_VERSION_1_2) } {
             JNI_OK => callback(unsafe { Env::from_jni_void_ref(&env) }),
             JNI_EDETACHED => match unsafe { (**java_vm).AttachCurrentThread.unwrap()(java_vm, &mut env, null_mut()) } {
-                JNI_OK => callback(unsafe { Env::from_jni_void_ref(&env) }),
+                JNI_OK => {
+                    detach_on_thread_exit(java_vm);
+                    callback(unsafe { Env::from_jni_void_ref(&env) })
+                },
                 unexpected => panic!("AttachCurrentThread returned unknown error: {}", unexpected),
             },
             JNI_EVERSION => panic!("GetEnv returned JNI_EVERSION"),
             unexpected => panic!("GetEnv returned unknown error: {}", unexpected),
         }
     }
+
+    /// Run `callback` with an [Env] for the current thread of the only loaded VM, attaching the thread if necessary (see
+    /// [with_env].)  Used by code generated with `static_env = "implicit"`.
+    ///
+    /// Panics if no VM - or more than one VM - is loaded.  The VM is kept loaded until `callback` returns:  unloading it
+    /// - or calling [set_vm_backend] - from within `callback` will deadlock.
+    ///
+    /// [Env]:              struct.Env.html
+    /// [with_env]:         #method.with_env
+    /// [set_vm_backend]:   fn.set_vm_backend.html
+    pub fn with_implicit_env<F, R>(callback: F) -> R
+    where
+        F: FnOnce(&Env) -> R,
+    {
+        let vms = VMS.read().unwrap(); // Held until callback returns, so the VM can't be unloaded out from under it.
+        let gen_vm = vms.sole_vm().expect("static_env = \"implicit\" requires exactly one loaded VM");
+        vms.use_vm(gen_vm, |vm| vm.with_env(callback))
+    }
+
+    /// Attach the current thread (if it isn't already) until the returned [AttachGuard] is dropped.
+    ///
+    /// [AttachGuard]:  struct.AttachGuard.html
+    pub fn attach(&self) -> AttachGuard<'_> {
+        self.attach_with(&AttachOptions::new())
+    }
+
+    /// Attach the current thread (if it isn't already) with a name, thread group, and/or as a daemon, until the
+    /// returned [AttachGuard] is dropped.
+    ///
+    /// [AttachGuard]:  struct.AttachGuard.html
+    pub fn attach_with(&self, options: &AttachOptions) -> AttachGuard<'_> {
+        unsafe { AttachGuard::new(self, options) }
+    }
 }
 
 unsafe impl Send for VM {}